@@ -1,6 +1,10 @@
 local M = {}
 
 local line_locators = require("line_locators")
+local mi = require("mi")
+local workspace = require("workspace")
+local panics = require("panics")
+local state_file = require("state_file")
 
 -- Namespace for our breakpoint extmarks
 local ns_id = vim.api.nvim_create_namespace("rust_termdebug_breakpoints")
@@ -8,12 +12,57 @@ local ns_id = vim.api.nvim_create_namespace("rust_termdebug_breakpoints")
 -- Store breakpoints by buffer: { [bufnr] = { [line] = extmark_id } }
 local breakpoint_marks = {}
 
+-- Per-breakpoint attributes by buffer: { [bufnr] = { [extmark_id] = info } }
+-- info: { kind = "logpoint"?, condition = string?, message = string?, ignore_count = number?,
+--         enabled = false? (nil = enabled), group = string?, hits = number?, number = string?, temporary = boolean? (read back from GDB, not persisted),
+--         placed_by = string?, confidence = number?, unconfirmed = true? (how a restored breakpoint's line was located, not persisted),
+--         resolved_line = number?, address = string?, unresolved = true? (where GDB put the breakpoint, for this session only) }
+local breakpoint_info = {}
+
+-- Breakpoints on function symbols rather than file:line, so they survive code movement
+-- { { symbol = string, enabled = false?, group = string?, number = string?, hits = number?, file = string?, line = number? } }
+-- file/line are where GDB resolved the symbol, when known
+local function_breakpoints = {}
+
+-- Breakpoints of deleted groups, kept as persistence entries (with parked = true)
+-- until the group is restored
+local parked_breakpoints = {}
+
+-- Breakpoints that couldn't be restored, kept as persistence entries (with
+-- orphaned = true and the reason) until they're re-anchored or discarded
+local orphaned_breakpoints = {}
+
+-- Map GDB breakpoint numbers to what they belong to:
+-- { [number] = { bufnr, extmark_id } } or { [number] = { symbol } }
+local gdb_breakpoints = {}
+
+-- Attributes copied between extmark info, get_all() results and the persistence file
+-- id identifies a breakpoint across editors sharing the file, wherever it moves
+local INFO_FIELDS = { "id", "kind", "condition", "message", "ignore_count", "enabled", "group" }
+
 -- Track which buffers have the deletion handler set up
 local buffers_with_handlers = {}
 
 -- Persistence settings (nil = disabled, table = config with line_locator, etc.)
 local persistence_config = nil
 
+-- Git branch whose breakpoint set is loaded, when persisting per branch
+local active_branch = nil
+
+-- When the loaded persistence file was first written, kept across saves
+local file_created_at = nil
+
+-- Persistence file content as we last read or wrote it, and its entries by key,
+-- for merging in changes saved by other editors
+local synced_content = nil
+local synced_entries = {}
+
+-- Content of our most recent saves, so the file watcher doesn't merge our own writes
+local own_writes = {}
+
+-- Watches the persistence file's directory for saves from other editors
+local file_watcher = nil
+
 -- Helper to get a shortened path for user messages
 local function short_path(filepath)
     local cwd = vim.fn.getcwd()
@@ -25,29 +74,137 @@ end
 
 -- Get the workspace-specific persistence file path
 local function get_persistence_file()
-    local workspace_root
+    if active_branch then
+        -- One file per branch; branch names with slashes get subdirectories
+        local file = workspace.state_dir() .. "/branches/" .. active_branch .. ".json"
+        vim.fn.mkdir(vim.fn.fnamemodify(file, ":h"), "p")
+        return file
+    end
+    return workspace.state_dir() .. "/breakpoints.json"
+end
+
+-- Look up the attributes recorded for a breakpoint's extmark
+local function get_info(bufnr, extmark_id)
+    return breakpoint_info[bufnr] and breakpoint_info[bufnr][extmark_id] or {}
+end
+
+-- Copy the breakpoint attributes in INFO_FIELDS from src into dest
+local function copy_info(dest, src)
+    for _, field in ipairs(INFO_FIELDS) do
+        dest[field] = src[field]
+    end
+    return dest
+end
+
+-- Stop tracking the breakpoint on a line (0-indexed); returns the removed extmark id
+local function forget_mark(bufnr, line)
+    local extmark_id = breakpoint_marks[bufnr] and breakpoint_marks[bufnr][line]
+    if not extmark_id then
+        return nil
+    end
+
+    breakpoint_marks[bufnr][line] = nil
+    local info = breakpoint_info[bufnr] and breakpoint_info[bufnr][extmark_id]
+    if info then
+        if info.number then
+            gdb_breakpoints[info.number] = nil
+        end
+        breakpoint_info[bufnr][extmark_id] = nil
+    end
+    return extmark_id
+end
+
+-- Find the line key a tracked extmark was stored under
+local function line_of_extmark(bufnr, extmark_id)
+    for line, id in pairs(breakpoint_marks[bufnr] or {}) do
+        if id == extmark_id then
+            return line
+        end
+    end
+    return nil
+end
+
+-- New id for a breakpoint
+local function new_id()
+    return string.format("%x%06x", (vim.uv or vim.loop).hrtime(), math.random(0, 0xffffff))
+end
+
+-- Id of a persistence entry; entries saved before ids existed get one from
+-- their location, which every editor reading the same file agrees on
+local function entry_id(entry)
+    return entry.id or vim.fn.sha256(string.format("%s:%d", entry.file, entry.line)):sub(1, 16)
+end
 
-    -- Try to get the cargo workspace root
-    local metadata_json = vim.fn.system("cargo metadata --no-deps --format-version=1 2>/dev/null")
-    if vim.v.shell_error == 0 then
-        local ok, metadata = pcall(vim.json.decode, metadata_json)
-        if ok and metadata and metadata.workspace_root then
-            workspace_root = metadata.workspace_root
+-- Find the tracked extmark of the breakpoint with an id
+local function find_mark_by_id(id)
+    for bufnr, infos in pairs(breakpoint_info) do
+        for extmark_id, info in pairs(infos) do
+            if info.id == id and vim.api.nvim_buf_is_valid(bufnr) then
+                return bufnr, extmark_id
+            end
+        end
+    end
+    return nil
+end
+
+-- Find the tracked extmark on a file and line (1-indexed), using its current position
+local function find_mark_at(file, line)
+    for bufnr, marks in pairs(breakpoint_marks) do
+        if vim.api.nvim_buf_is_valid(bufnr) and vim.api.nvim_buf_get_name(bufnr) == file then
+            for _, extmark_id in pairs(marks) do
+                local mark = vim.api.nvim_buf_get_extmark_by_id(bufnr, ns_id, extmark_id, {})
+                if mark and #mark > 0 and mark[1] + 1 == line then
+                    return bufnr, extmark_id
+                end
+            end
         end
     end
+    return nil
+end
+
+-- Record which GDB breakpoint number an extmark corresponds to
+local function assign_number(bufnr, extmark_id, number)
+    local info = breakpoint_info[bufnr] and breakpoint_info[bufnr][extmark_id]
+    if not info then
+        return
+    end
+    if info.number and info.number ~= number then
+        gdb_breakpoints[info.number] = nil
+    end
+    info.number = number
+    gdb_breakpoints[number] = { bufnr = bufnr, extmark_id = extmark_id }
+end
 
-    -- Fallback to current directory if not in a cargo workspace
-    if not workspace_root then
-        workspace_root = vim.fn.getcwd()
+-- Forget the GDB breakpoint number of a breakpoint being deleted and sent
+-- again, so GDB's breakpoint-deleted for the old number leaves its extmark alone
+local function release_number(info)
+    if info.number then
+        gdb_breakpoints[info.number] = nil
+        info.number = nil
     end
+end
 
-    -- Create .rust-termdebug.nvim directory if it doesn't exist
-    local dir = workspace_root .. "/.rust-termdebug.nvim"
-    if vim.fn.isdirectory(dir) == 0 then
-        vim.fn.mkdir(dir, "p")
+-- Forget all GDB breakpoint numbers, eg before the breakpoints are recreated
+local function clear_numbers()
+    for _, infos in pairs(breakpoint_info) do
+        for _, info in pairs(infos) do
+            info.number = nil
+        end
     end
+    for _, fb in ipairs(function_breakpoints) do
+        fb.number = nil
+    end
+    gdb_breakpoints = {}
+end
 
-    return dir .. "/breakpoints.json"
+-- Find a tracked function breakpoint by symbol; returns the entry and its index
+local function find_function(symbol)
+    for i, fb in ipairs(function_breakpoints) do
+        if fb.symbol == symbol then
+            return fb, i
+        end
+    end
+    return nil
 end
 
 -- Set up buffer-local autocmd to clean up extmarks when lines are deleted
@@ -78,7 +235,7 @@ local function setup_buffer_deletion_handler(bufnr)
 
             -- Remove tracked breakpoints that no longer have extmarks
             for _, line in ipairs(marks_to_remove) do
-                breakpoint_marks[bufnr][line] = nil
+                forget_mark(bufnr, line)
             end
 
             -- If no more breakpoints in this buffer, clean up the autocmd
@@ -86,6 +243,7 @@ local function setup_buffer_deletion_handler(bufnr)
                 vim.api.nvim_del_augroup_by_name("RustTermdebugBreakpoints_" .. bufnr)
                 buffers_with_handlers[bufnr] = nil
                 breakpoint_marks[bufnr] = nil
+                breakpoint_info[bufnr] = nil
             end
         end,
     })
@@ -112,39 +270,349 @@ local function cleanup_buffer_handler(bufnr)
     end
 end
 
--- Create a breakpoint and track it with an extmark
-M.create = function()
-    local bufnr = vim.api.nvim_get_current_buf()
-    local line = vim.api.nvim_win_get_cursor(0)[1] - 1 -- 0-indexed
+-- Extmark sign options for a breakpoint with the given attributes
+local function sign_opts(info)
+    if info.unconfirmed then
+        return { sign_text = "●?", sign_hl_group = "DiagnosticHint" }
+    elseif info.enabled == false then
+        return { sign_text = "○", sign_hl_group = "Comment" }
+    elseif info.unresolved then
+        return { sign_text = "◌", sign_hl_group = "DiagnosticWarn" }
+    elseif info.kind == "logpoint" then
+        return { sign_text = "◇", sign_hl_group = "DiagnosticInfo" }
+    elseif info.condition then
+        return { sign_text = "◆", sign_hl_group = "DiagnosticWarn" }
+    end
+    return { sign_text = "●", sign_hl_group = "DiagnosticError" }
+end
+
+-- Virtual text describing how often a breakpoint has been hit, or nil
+local function hit_label(info)
+    if info.ignore_count then
+        return string.format("%d/%d hits", info.hits or 0, info.ignore_count + 1)
+    elseif info.hits and info.hits > 0 then
+        return string.format("%d hit%s", info.hits, info.hits == 1 and "" or "s")
+    end
+    return nil
+end
+
+-- Full extmark options for a breakpoint: sign plus hit count virtual text
+local function mark_opts(info)
+    local opts = sign_opts(info)
+    local label = hit_label(info)
+    if label then
+        opts.virt_text = { { label, "Comment" } }
+        opts.virt_text_pos = "eol"
+    end
+    return opts
+end
+
+-- Redraw an existing breakpoint extmark after its attributes changed
+local function render_mark(bufnr, extmark_id)
+    local mark = vim.api.nvim_buf_get_extmark_by_id(bufnr, ns_id, extmark_id, {})
+    if not mark or #mark == 0 then
+        return
+    end
+
+    local opts = mark_opts(get_info(bufnr, extmark_id))
+    opts.id = extmark_id
+    vim.api.nvim_buf_set_extmark(bufnr, ns_id, mark[1], 0, opts)
+end
 
+-- Create (or update) the extmark for a breakpoint and record its attributes
+local function place_mark(bufnr, line, info)
     -- Initialize buffer tracking if needed
     if not breakpoint_marks[bufnr] then
         breakpoint_marks[bufnr] = {}
     end
+    if not breakpoint_info[bufnr] then
+        breakpoint_info[bufnr] = {}
+    end
 
     -- Set up deletion handler for this buffer if not already done
     setup_buffer_deletion_handler(bufnr)
 
-    -- Create an extmark at this location
-    local extmark_id = vim.api.nvim_buf_set_extmark(bufnr, ns_id, line, 0, {
-        sign_text = "●",
-        sign_hl_group = "DiagnosticError",
-    })
+    info.id = info.id or new_id()
+
+    -- Reuse the existing extmark on this line so it isn't duplicated
+    local opts = mark_opts(info)
+    opts.id = breakpoint_marks[bufnr][line]
+
+    local extmark_id = vim.api.nvim_buf_set_extmark(bufnr, ns_id, line, 0, opts)
 
     breakpoint_marks[bufnr][line] = extmark_id
+    breakpoint_info[bufnr][extmark_id] = info
+    return extmark_id
+end
+
+-- Send the GDB commands that create a breakpoint with the given attributes
+-- Logpoints use dprintf, which prints and continues instead of stopping
+-- $bpnum is GDB's number for the most recently created breakpoint
+local function send_break(file, line, info)
+    if info.kind == "logpoint" then
+        vim.fn.TermDebugSendCommand(string.format("dprintf %s:%d,%s", file, line, info.message))
+    else
+        vim.fn.TermDebugSendCommand(string.format("break %s:%d", file, line))
+    end
+    if info.condition then
+        vim.fn.TermDebugSendCommand("condition $bpnum " .. info.condition)
+    end
+    if info.ignore_count then
+        vim.fn.TermDebugSendCommand(string.format("ignore $bpnum %d", info.ignore_count))
+    end
+    if info.enabled == false then
+        vim.fn.TermDebugSendCommand("disable $bpnum")
+    end
+end
+
+-- Delete the GDB breakpoint behind a tracked line (0-indexed), by number when known
+local function send_delete(bufnr, line, info)
+    if info.number then
+        vim.fn.TermDebugSendCommand("delete " .. info.number)
+    else
+        local filename = vim.api.nvim_buf_get_name(bufnr)
+        vim.fn.TermDebugSendCommand(string.format("clear %s:%d", filename, line + 1))
+    end
+end
+
+-- Create or update the breakpoint on a line (0-indexed), applying attribute
+-- changes on top of whatever the breakpoint already had
+local function update_breakpoint(bufnr, line, changes)
+    local existing = breakpoint_marks[bufnr] and breakpoint_marks[bufnr][line]
+    local info = existing and copy_info({}, get_info(bufnr, existing)) or {}
+    for field, value in pairs(changes) do
+        info[field] = value
+    end
+
+    local filename = vim.api.nvim_buf_get_name(bufnr)
+
+    -- Replace any GDB breakpoint already on this line so it isn't doubled
+    if existing then
+        local old_info = get_info(bufnr, existing)
+        pcall(send_delete, bufnr, line, old_info)
+        release_number(old_info)
+    end
+
+    place_mark(bufnr, line, info)
+
+    -- Try to create the actual GDB breakpoint if termdebug is running
+    pcall(send_break, filename, line + 1, info)
+
+    -- Save to disk immediately
+    M.save_to_disk()
+end
+
+-- Create a breakpoint and track it with an extmark
+M.create = function()
+    local bufnr = vim.api.nvim_get_current_buf()
+    local line = vim.api.nvim_win_get_cursor(0)[1] - 1 -- 0-indexed
+
+    -- Keep a breakpoint already on this line, with its condition and other
+    -- attributes, rather than resetting it and giving GDB a second one
+    if breakpoint_marks[bufnr] and breakpoint_marks[bufnr][line] then
+        return
+    end
+
+    -- Create an extmark at this location
+    place_mark(bufnr, line, {})
 
     -- Try to create the actual GDB breakpoint if termdebug is running
     -- Use pcall to avoid errors if termdebug isn't active
     pcall(vim.cmd, "Break")
 
+    -- GDB doesn't announce breakpoints inserted over termdebug's MI channel,
+    -- so ask for the breakpoint table to learn the new breakpoint's number
+    if mi.is_active() then
+        vim.defer_fn(M.sync, 100)
+    end
+
     -- Save to disk immediately
     M.save_to_disk()
 end
 
-M.delete_all = function()
-    -- Try to delete in GDB if termdebug is running
-    pcall(vim.fn.TermDebugSendCommand, "d")
+-- Create a breakpoint that only stops when a GDB expression is true
+M.create_conditional = function()
+    local bufnr = vim.api.nvim_get_current_buf()
+    local line = vim.api.nvim_win_get_cursor(0)[1] - 1 -- 0-indexed
+    local existing = breakpoint_marks[bufnr] and breakpoint_marks[bufnr][line]
+
+    vim.ui.input({
+        prompt = "Breakpoint condition: ",
+        default = existing and get_info(bufnr, existing).condition or "",
+    }, function(condition)
+        if not condition or vim.trim(condition) == "" then
+            return
+        end
+
+        update_breakpoint(bufnr, line, { condition = vim.trim(condition) })
+    end)
+end
+
+-- Create a logpoint that prints a message via GDB's dprintf without stopping
+-- The input is the dprintf argument list, eg "x = %d\n", x
+M.create_logpoint = function()
+    local bufnr = vim.api.nvim_get_current_buf()
+    local line = vim.api.nvim_win_get_cursor(0)[1] - 1 -- 0-indexed
+    local existing = breakpoint_marks[bufnr] and breakpoint_marks[bufnr][line]
+
+    vim.ui.input({
+        prompt = 'Logpoint ("format", args): ',
+        default = existing and get_info(bufnr, existing).message or '"\\n"',
+    }, function(message)
+        if not message or vim.trim(message) == "" then
+            return
+        end
+
+        update_breakpoint(bufnr, line, { kind = "logpoint", message = vim.trim(message) })
+    end)
+end
 
+-- Create a breakpoint that only stops on the Nth hit, via GDB's ignore count
+M.create_hit_count = function()
+    local bufnr = vim.api.nvim_get_current_buf()
+    local line = vim.api.nvim_win_get_cursor(0)[1] - 1 -- 0-indexed
+    local existing = breakpoint_marks[bufnr] and breakpoint_marks[bufnr][line]
+    local ignore_count = existing and get_info(bufnr, existing).ignore_count
+
+    vim.ui.input({
+        prompt = "Stop on hit number: ",
+        default = ignore_count and tostring(ignore_count + 1) or "",
+    }, function(input)
+        local hit = tonumber(input)
+        if not hit or hit < 1 or hit ~= math.floor(hit) then
+            if input and vim.trim(input) ~= "" then
+                vim.notify("Hit number must be a positive integer", vim.log.levels.WARN)
+            end
+            return
+        end
+
+        update_breakpoint(bufnr, line, { ignore_count = hit - 1 })
+    end)
+end
+
+-- List function names matching a regex: from GDB's debug info when a session
+-- is running, otherwise from LSP workspace symbols
+local function list_functions(pattern, callback)
+    if mi.is_active() then
+        local escaped = pattern:gsub('[\\"]', "\\%0")
+        mi.send(string.format('-symbol-info-functions --name "%s"', escaped), function(record)
+            local names, seen = {}, {}
+            local debug = record.results.symbols and record.results.symbols.debug or {}
+            for _, source in ipairs(debug) do
+                for _, sym in ipairs(source.symbols or {}) do
+                    if sym.name and not seen[sym.name] then
+                        seen[sym.name] = true
+                        table.insert(names, sym.name)
+                    end
+                end
+            end
+            table.sort(names)
+            callback(names)
+        end)
+        return
+    end
+
+    vim.lsp.buf_request_all(0, "workspace/symbol", { query = pattern }, function(responses)
+        local names, seen = {}, {}
+        for _, response in pairs(responses) do
+            for _, sym in ipairs(response.result or {}) do
+                -- 12 = Function, 6 = Method
+                if sym.kind == 12 or sym.kind == 6 then
+                    local name = sym.name
+                    if sym.containerName and sym.containerName ~= "" then
+                        name = sym.containerName:gsub("^impl%s+", "") .. "::" .. name
+                    end
+                    if not seen[name] then
+                        seen[name] = true
+                        table.insert(names, name)
+                    end
+                end
+            end
+        end
+        table.sort(names)
+        callback(names)
+    end)
+end
+
+-- Set a breakpoint on a function symbol, eg "my_crate::parser::parse"
+M.add_function = function(symbol)
+    if find_function(symbol) then
+        vim.notify("Breakpoint already set on " .. symbol, vim.log.levels.INFO)
+        return
+    end
+
+    table.insert(function_breakpoints, { symbol = symbol })
+
+    -- Try to create the actual GDB breakpoint if termdebug is running
+    pcall(vim.fn.TermDebugSendCommand, "break " .. symbol)
+
+    -- Save to disk immediately
+    M.save_to_disk()
+end
+
+-- Delete the breakpoint on a function symbol
+M.delete_function = function(symbol)
+    local fb, index = find_function(symbol)
+    if not fb then
+        return false
+    end
+
+    table.remove(function_breakpoints, index)
+    if fb.number then
+        gdb_breakpoints[fb.number] = nil
+        pcall(vim.fn.TermDebugSendCommand, "delete " .. fb.number)
+    else
+        pcall(vim.fn.TermDebugSendCommand, "clear " .. symbol)
+    end
+
+    -- Save to disk immediately
+    M.save_to_disk()
+    return true
+end
+
+-- Pick a function from the binary (or LSP symbols) and set a breakpoint on it
+M.create_function = function()
+    vim.ui.input({ prompt = "Function regex: " }, function(pattern)
+        if not pattern or vim.trim(pattern) == "" then
+            return
+        end
+
+        list_functions(vim.trim(pattern), function(names)
+            if #names == 0 then
+                vim.notify("No functions match " .. pattern, vim.log.levels.WARN)
+                return
+            end
+
+            vim.ui.select(names, {
+                prompt = "Break on function:",
+                kind = "rust-termdebug-function-select",
+            }, function(symbol)
+                if symbol then
+                    M.add_function(symbol)
+                end
+            end)
+        end)
+    end)
+end
+
+-- Get all function breakpoints: { { symbol, enabled?, group?, hits?, file?, line? } }
+M.get_functions = function()
+    local functions = {}
+    for _, fb in ipairs(function_breakpoints) do
+        table.insert(functions, {
+            symbol = fb.symbol,
+            enabled = fb.enabled,
+            group = fb.group,
+            hits = fb.hits,
+            file = fb.file,
+            line = fb.line,
+        })
+    end
+    return functions
+end
+
+-- Forget every tracked breakpoint and clear its extmark, without touching GDB or disk
+local function clear_tracked()
     -- Clear all extmarks and clean up handlers
     for bufnr, _ in pairs(breakpoint_marks) do
         if vim.api.nvim_buf_is_valid(bufnr) then
@@ -153,6 +621,16 @@ M.delete_all = function()
         cleanup_buffer_handler(bufnr)
     end
     breakpoint_marks = {}
+    breakpoint_info = {}
+    function_breakpoints = {}
+    gdb_breakpoints = {}
+end
+
+M.delete_all = function()
+    -- Try to delete in GDB if termdebug is running
+    pcall(vim.fn.TermDebugSendCommand, "d")
+
+    clear_tracked()
 
     -- Save to disk immediately
     M.save_to_disk()
@@ -160,16 +638,22 @@ end
 
 -- clear breakpoints on the current line
 M.delete_curline = function()
-    -- Try to delete in GDB if termdebug is running
-    pcall(vim.cmd, "Clear")
-
-    -- Remove the extmark on the current line
     local bufnr = vim.api.nvim_get_current_buf()
     local line = vim.api.nvim_win_get_cursor(0)[1] - 1 -- 0-indexed
+    local existing = breakpoint_marks[bufnr] and breakpoint_marks[bufnr][line]
+    local number = existing and get_info(bufnr, existing).number
 
-    if breakpoint_marks[bufnr] and breakpoint_marks[bufnr][line] then
-        vim.api.nvim_buf_del_extmark(bufnr, ns_id, breakpoint_marks[bufnr][line])
-        breakpoint_marks[bufnr][line] = nil
+    -- Try to delete in GDB if termdebug is running
+    if number then
+        pcall(vim.fn.TermDebugSendCommand, "delete " .. number)
+    else
+        pcall(vim.cmd, "Clear")
+    end
+
+    -- Remove the extmark on the current line
+    local extmark_id = forget_mark(bufnr, line)
+    if extmark_id then
+        vim.api.nvim_buf_del_extmark(bufnr, ns_id, extmark_id)
     end
 
     -- Save to disk immediately
@@ -179,15 +663,11 @@ end
 -- Delete breakpoint at a specific buffer and line (0-indexed)
 M.delete_at = function(bufnr, line)
     if breakpoint_marks[bufnr] and breakpoint_marks[bufnr][line] then
-        -- Delete the extmark
-        vim.api.nvim_buf_del_extmark(bufnr, ns_id, breakpoint_marks[bufnr][line])
-        breakpoint_marks[bufnr][line] = nil
-
         -- Try to delete in GDB if termdebug is running
-        -- We need to find the GDB breakpoint number for this location
-        local filename = vim.api.nvim_buf_get_name(bufnr)
-        local gdb_line = line + 1 -- GDB uses 1-indexed lines
-        pcall(vim.fn.TermDebugSendCommand, string.format("clear %s:%d", filename, gdb_line))
+        pcall(send_delete, bufnr, line, get_info(bufnr, breakpoint_marks[bufnr][line]))
+
+        -- Delete the extmark
+        vim.api.nvim_buf_del_extmark(bufnr, ns_id, forget_mark(bufnr, line))
 
         -- Save to disk immediately
         M.save_to_disk()
@@ -214,6 +694,104 @@ M.toggle = function()
     end
 end
 
+-- Stored value of the enabled field: only disabled breakpoints record it
+local function enabled_field(enabled)
+    if enabled then
+        return nil
+    end
+    return false
+end
+
+-- Enable or disable the breakpoint on a line (0-indexed) without deleting it
+-- Returns false if there's no breakpoint on the line
+M.set_enabled_at = function(bufnr, line, enabled)
+    local extmark_id = breakpoint_marks[bufnr] and breakpoint_marks[bufnr][line]
+    if not extmark_id then
+        return false
+    end
+
+    local info = get_info(bufnr, extmark_id)
+    info.enabled = enabled_field(enabled)
+    if not info.number then
+        -- GDB's number isn't known yet; recreate the breakpoint in the new state
+        update_breakpoint(bufnr, line, {})
+        return true
+    end
+
+    pcall(vim.fn.TermDebugSendCommand, (enabled and "enable " or "disable ") .. info.number)
+    render_mark(bufnr, extmark_id)
+
+    -- Save to disk immediately
+    M.save_to_disk()
+    return true
+end
+
+-- Enable or disable the breakpoint on a function symbol without deleting it
+M.set_function_enabled = function(symbol, enabled)
+    local fb = find_function(symbol)
+    if not fb then
+        return false
+    end
+
+    fb.enabled = enabled_field(enabled)
+    if fb.number then
+        pcall(vim.fn.TermDebugSendCommand, (enabled and "enable " or "disable ") .. fb.number)
+    end
+
+    -- Save to disk immediately
+    M.save_to_disk()
+    return true
+end
+
+-- Enable or disable the breakpoint on the current line
+M.toggle_enabled = function()
+    local bufnr = vim.api.nvim_get_current_buf()
+    local line = vim.api.nvim_win_get_cursor(0)[1] - 1 -- 0-indexed
+    local existing = breakpoint_marks[bufnr] and breakpoint_marks[bufnr][line]
+    if not existing then
+        vim.notify("No breakpoint on this line", vim.log.levels.INFO)
+        return
+    end
+
+    M.set_enabled_at(bufnr, line, get_info(bufnr, existing).enabled == false)
+end
+
+-- Accept where a low-confidence restore placed the breakpoint on a line (0-indexed)
+-- Returns false if there's no unconfirmed breakpoint on the line
+M.confirm_at = function(bufnr, line)
+    local extmark_id = breakpoint_marks[bufnr] and breakpoint_marks[bufnr][line]
+    local info = extmark_id and get_info(bufnr, extmark_id)
+    if not info or not info.unconfirmed then
+        return false
+    end
+
+    info.unconfirmed = nil
+    render_mark(bufnr, extmark_id)
+    return true
+end
+
+-- Confirm the breakpoint on the current line
+M.confirm_curline = function()
+    local bufnr = vim.api.nvim_get_current_buf()
+    local line = vim.api.nvim_win_get_cursor(0)[1] - 1 -- 0-indexed
+    if not M.confirm_at(bufnr, line) then
+        vim.notify("No unconfirmed breakpoint on this line", vim.log.levels.INFO)
+    end
+end
+
+-- Confirm every unconfirmed breakpoint; returns how many were confirmed
+M.confirm_all = function()
+    local count = 0
+    for bufnr, marks in pairs(breakpoint_marks) do
+        for line in pairs(marks) do
+            if M.confirm_at(bufnr, line) then
+                count = count + 1
+            end
+        end
+    end
+    return count
+end
+
 -- Get all breakpoint locations tracked by extmarks
 M.get_all = function()
     local breakpoints = {}
@@ -224,10 +802,20 @@ M.get_all = function()
             for _, extmark_id in pairs(marks) do
                 local mark = vim.api.nvim_buf_get_extmark_by_id(bufnr, ns_id, extmark_id, {})
                 if mark and #mark > 0 then
-                    table.insert(breakpoints, {
-                        file = bufname,
-                        line = mark[1] + 1, -- Convert to 1-indexed
-                    })
+                    local info = get_info(bufnr, extmark_id)
+                    table.insert(
+                        breakpoints,
+                        copy_info({
+                            file = bufname,
+                            line = mark[1] + 1, -- Convert to 1-indexed
+                            hits = info.hits,
+                            placed_by = info.placed_by,
+                            confidence = info.confidence,
+                            unconfirmed = info.unconfirmed,
+                            unresolved = info.unresolved,
+                            address = info.address,
+                        }, info)
+                    )
                 end
             end
         end
@@ -240,108 +828,517 @@ end
 M.restore_all = function()
     local breakpoints = M.get_all()
 
-    if #breakpoints == 0 then
+    if #breakpoints == 0 and #function_breakpoints == 0 then
         return
     end
 
+    -- GDB assigns new numbers as the breakpoints are recreated
+    clear_numbers()
+
+    -- Hit counts start over with the new GDB breakpoints
+    for bufnr, infos in pairs(breakpoint_info) do
+        for extmark_id, info in pairs(infos) do
+            if info.hits then
+                info.hits = nil
+                render_mark(bufnr, extmark_id)
+            end
+        end
+    end
+
     for _, bp in ipairs(breakpoints) do
-        local cmd = string.format("break %s:%d", bp.file, bp.line)
-        vim.fn.TermDebugSendCommand(cmd)
+        send_break(bp.file, bp.line, bp)
+    end
+
+    for _, fb in ipairs(function_breakpoints) do
+        fb.hits = nil
+        vim.fn.TermDebugSendCommand("break " .. fb.symbol)
+        if fb.enabled == false then
+            vim.fn.TermDebugSendCommand("disable $bpnum")
+        end
     end
 
-    vim.notify("Restored " .. #breakpoints .. " breakpoint(s)", vim.log.levels.INFO)
+    local count = #breakpoints + #function_breakpoints
+    vim.notify("Restored " .. count .. " breakpoint(s)", vim.log.levels.INFO)
 end
 
--- Save breakpoints to disk for persistence across sessions
-M.save_to_disk = function()
-    if not persistence_config then
-        return
+-- Location of a GDB breakpoint record as fullname, line (1-indexed)
+-- Breakpoints with several locations (eg generic functions) use the first one
+local function gdb_location(bkpt, sublocations)
+    local location = bkpt
+    if not bkpt.fullname then
+        location = (bkpt.locations and bkpt.locations[1]) or sublocations[bkpt.number] or {}
     end
+    return location.fullname, tonumber(location.line)
+end
 
-    local breakpoints_to_save = {}
-    local strategy = line_locators.get(persistence_config.line_locator)
+-- File and line (1-indexed) a breakpoint was set on, before GDB resolved it
+-- to a line with code; pending breakpoints only have this
+local function requested_location(bkpt)
+    local location = bkpt.pending or bkpt["original-location"] or ""
+    local file, line = location:match("^(.+):(%d+)$")
+    if not file then
+        file, line = location:match("^%-source (%S+) %-line (%d+)$")
+    end
+    if not file then
+        return nil
+    end
+    return vim.fn.fnamemodify(file, ":p"), tonumber(line)
+end
 
-    -- Collect all breakpoint locations from extmarks
-    for bufnr, marks in pairs(breakpoint_marks) do
-        if vim.api.nvim_buf_is_valid(bufnr) then
-            local bufname = vim.api.nvim_buf_get_name(bufnr)
-            -- Only save breakpoints for named buffers (files on disk)
-            if bufname ~= "" then
-                -- Load buffer if needed for strategies that need line content
-                if strategy and strategy.prepare and not vim.api.nvim_buf_is_loaded(bufnr) then
-                    vim.fn.bufload(bufnr)
-                end
+-- Move a breakpoint's extmark to another line (0-indexed), unless another
+-- breakpoint is already there; returns true if it moved
+local function move_mark(bufnr, extmark_id, line)
+    local mark = vim.api.nvim_buf_get_extmark_by_id(bufnr, ns_id, extmark_id, {})
+    local marks = breakpoint_marks[bufnr]
+    if not mark or #mark == 0 or mark[1] == line or line >= vim.api.nvim_buf_line_count(bufnr) then
+        return false
+    end
+    if marks[line] and marks[line] ~= extmark_id then
+        return false
+    end
 
-                for _, extmark_id in pairs(marks) do
-                    local mark = vim.api.nvim_buf_get_extmark_by_id(bufnr, ns_id, extmark_id, {})
-                    if mark and #mark > 0 then
-                        local line_1indexed = mark[1] + 1
-                        local bp_entry = {
-                            file = bufname,
-                            line = line_1indexed,
-                        }
-
-                        -- Let strategy prepare additional data for persistence
-                        if strategy and strategy.prepare then
-                            local lines = vim.api.nvim_buf_get_lines(bufnr, mark[1], mark[1] + 1, false)
-                            if lines and #lines > 0 then
-                                local prepared = strategy.prepare(lines[1])
-                                if prepared then
-                                    bp_entry.locator_data = prepared
-                                end
-                            end
-                        end
-
-                        table.insert(breakpoints_to_save, bp_entry)
-                    end
+    local old_line = line_of_extmark(bufnr, extmark_id)
+    if old_line then
+        marks[old_line] = nil
+    end
+    marks[line] = extmark_id
+
+    local opts = mark_opts(get_info(bufnr, extmark_id))
+    opts.id = extmark_id
+    vim.api.nvim_buf_set_extmark(bufnr, ns_id, line, 0, opts)
+    return true
+end
+
+-- Match a breakpoint's extmark to where GDB resolved it: move it to the line
+-- GDB will really stop on, and flag it while GDB has no code address for it
+-- Returns true if the extmark moved
+local function apply_resolution(bufnr, extmark_id, bkpt, sublocations)
+    local info = get_info(bufnr, extmark_id)
+    local fullname, line = gdb_location(bkpt, sublocations)
+    local unresolved = (bkpt.pending ~= nil or bkpt.addr == "<PENDING>") or nil
+    local address = not unresolved and bkpt.addr or nil
+
+    -- Only follow GDB when its answer changes, so a mark moved by edits since
+    -- the last build isn't dragged back to the old line on every sync
+    local moved = false
+    if line and line ~= info.resolved_line and fullname == vim.api.nvim_buf_get_name(bufnr) then
+        local mark = vim.api.nvim_buf_get_extmark_by_id(bufnr, ns_id, extmark_id, {})
+        info.resolved_line = line
+        moved = move_mark(bufnr, extmark_id, line - 1)
+        if moved then
+            vim.notify(
+                string.format(
+                    "Breakpoint at %s:%d moved to line %d, where GDB can stop",
+                    short_path(fullname),
+                    mark[1] + 1,
+                    line
+                ),
+                vim.log.levels.INFO
+            )
+        end
+    end
+
+    if unresolved ~= info.unresolved or address ~= info.address then
+        info.unresolved = unresolved
+        info.address = address
+        render_mark(bufnr, extmark_id)
+    end
+    return moved
+end
+
+-- Attributes of a breakpoint created directly in GDB
+local function info_from_gdb(bkpt)
+    local info = {
+        condition = bkpt.cond,
+        ignore_count = tonumber(bkpt.ignore),
+        enabled = enabled_field(bkpt.enabled ~= "n"),
+        temporary = bkpt.disp == "del" or nil,
+        hits = tonumber(bkpt.times),
+    }
+    if bkpt.type == "dprintf" then
+        local script = type(bkpt.script) == "table" and bkpt.script[1] or ""
+        info.kind = "logpoint"
+        info.message = script:match("^printf%s+(.*)$") or script
+    end
+    return info
+end
+
+-- Apply one GDB breakpoint record to the tracked extmarks
+-- Returns true if anything that gets persisted changed
+local function apply_gdb_breakpoint(bkpt, sublocations)
+    if bkpt.type ~= "breakpoint" and bkpt.type ~= "dprintf" then
+        return false
+    end
+
+    -- Function breakpoints are matched by the symbol they were set on
+    local tracked = gdb_breakpoints[bkpt.number]
+    local fb = tracked and tracked.symbol and find_function(tracked.symbol)
+        or (not tracked and find_function(bkpt["original-location"]))
+    if fb then
+        local fullname, line = gdb_location(bkpt, sublocations)
+        local enabled_changed = enabled_field(bkpt.enabled ~= "n") ~= fb.enabled
+        fb.number = bkpt.number
+        fb.enabled = enabled_field(bkpt.enabled ~= "n")
+        fb.hits = tonumber(bkpt.times)
+        fb.file = fullname
+        fb.line = line
+        gdb_breakpoints[bkpt.number] = { symbol = fb.symbol }
+        return enabled_changed
+    elseif tracked and tracked.symbol then
+        return false
+    elseif not tracked and panics.is_panic_symbol(bkpt["original-location"]) then
+        -- Panic breakpoints are managed by :RustDebugCatchPanic, not shown as marks
+        return false
+    end
+
+    local bufnr, extmark_id
+    if tracked then
+        bufnr, extmark_id = tracked.bufnr, tracked.extmark_id
+    else
+        -- Find the extmark the breakpoint was set from; GDB may have moved it
+        -- to the next line with code
+        local requested_file, requested_line = requested_location(bkpt)
+        if requested_file then
+            bufnr, extmark_id = find_mark_at(requested_file, requested_line)
+        end
+
+        local fullname, line = gdb_location(bkpt, sublocations)
+        if not bufnr and (not fullname or not line or vim.fn.filereadable(fullname) ~= 1) then
+            return false
+        end
+
+        if not bufnr then
+            bufnr, extmark_id = find_mark_at(fullname, line)
+        end
+        if not bufnr then
+            -- Breakpoint was created in the GDB REPL; show it as an extmark
+            bufnr = vim.fn.bufnr(fullname)
+            if bufnr == -1 then
+                bufnr = vim.fn.bufadd(fullname)
+            end
+            vim.fn.bufload(bufnr)
+            if line > vim.api.nvim_buf_line_count(bufnr) then
+                return false
+            end
+            local info = info_from_gdb(bkpt)
+            info.resolved_line = line
+            extmark_id = place_mark(bufnr, line - 1, info)
+            assign_number(bufnr, extmark_id, bkpt.number)
+            return true
+        end
+        assign_number(bufnr, extmark_id, bkpt.number)
+    end
+
+    local moved = apply_resolution(bufnr, extmark_id, bkpt, sublocations)
+
+    -- Pick up hit counts, conditions and enable/disable changed in the GDB REPL
+    -- (GDB's ignore field counts down as the breakpoint is hit, so it isn't synced back)
+    local info = get_info(bufnr, extmark_id)
+    local hits = tonumber(bkpt.times)
+    local enabled = enabled_field(bkpt.enabled ~= "n")
+    local changed = bkpt.cond ~= info.condition or enabled ~= info.enabled
+    if hits ~= info.hits or changed then
+        info.hits = hits
+        info.condition = bkpt.cond
+        info.enabled = enabled
+        render_mark(bufnr, extmark_id)
+    end
+    return changed or moved
+end
+
+-- Stop tracking a breakpoint that was deleted in GDB
+-- Returns true if an extmark was removed
+local function remove_gdb_breakpoint(number)
+    local tracked = gdb_breakpoints[number]
+    if not tracked then
+        return false
+    end
+
+    if tracked.symbol then
+        local _, index = find_function(tracked.symbol)
+        if index then
+            table.remove(function_breakpoints, index)
+        end
+        gdb_breakpoints[number] = nil
+        return true
+    end
+
+    local line = line_of_extmark(tracked.bufnr, tracked.extmark_id)
+    if line then
+        forget_mark(tracked.bufnr, line)
+    end
+    gdb_breakpoints[number] = nil
+    pcall(vim.api.nvim_buf_del_extmark, tracked.bufnr, ns_id, tracked.extmark_id)
+    return true
+end
+
+-- Reconcile tracked extmarks with GDB's full breakpoint table (a list of bkpt records)
+-- Adds extmarks for breakpoints set in GDB, removes ones deleted there and
+-- refreshes GDB numbers, hit counts and resolved lines
+-- With opts.mark_missing, extmarks GDB has no breakpoint for (eg because it
+-- rejected the line) are flagged as unresolved
+M.sync_from_gdb = function(bkpts, opts)
+    local changed = false
+
+    -- Old GDB versions list extra locations as separate "N.M" records
+    local sublocations = {}
+    local present = {}
+    for _, bkpt in ipairs(bkpts) do
+        local parent = bkpt.number and bkpt.number:match("^(%d+)%.")
+        if parent then
+            sublocations[parent] = sublocations[parent] or bkpt
+        elseif bkpt.number then
+            present[bkpt.number] = true
+        end
+    end
+
+    for _, bkpt in ipairs(bkpts) do
+        if bkpt.number and present[bkpt.number] then
+            changed = apply_gdb_breakpoint(bkpt, sublocations) or changed
+        end
+    end
+
+    for number in pairs(vim.deepcopy(gdb_breakpoints)) do
+        if not present[number] then
+            changed = remove_gdb_breakpoint(number) or changed
+        end
+    end
+
+    if opts and opts.mark_missing then
+        for bufnr, infos in pairs(breakpoint_info) do
+            for extmark_id, info in pairs(infos) do
+                if not info.number and not info.unresolved then
+                    info.unresolved = true
+                    render_mark(bufnr, extmark_id)
                 end
             end
         end
     end
 
-    -- Write to file
-    local file = io.open(get_persistence_file(), "w")
-    if file then
-        file:write(vim.json.encode(breakpoints_to_save))
-        file:close()
+    if changed then
+        M.save_to_disk()
     end
 end
 
--- Load breakpoints from disk and create extmarks
-M.load_from_disk = function()
-    if not persistence_config then
-        return
+-- Fetch GDB's breakpoint table over MI and reconcile the tracked extmarks with it
+M.sync = function()
+    mi.send("-break-list", function(record)
+        if record.class == "done" and record.results.BreakpointTable then
+            M.sync_from_gdb(record.results.BreakpointTable.body or {}, { mark_missing = true })
+        end
+    end)
+end
+
+-- Breakpoints typed into the GDB REPL show up as extmarks; hit counts update on each hit
+local function on_breakpoint_changed(record)
+    local bkpt = record.results.bkpt
+    if bkpt and bkpt.number and apply_gdb_breakpoint(bkpt, { [bkpt.number] = record.results[1] }) then
+        M.save_to_disk()
     end
+end
 
-    local file = io.open(get_persistence_file(), "r")
-    if not file then
-        return
+mi.on("breakpoint-created", on_breakpoint_changed)
+mi.on("breakpoint-modified", on_breakpoint_changed)
+
+-- Breakpoints deleted in the GDB REPL lose their extmarks
+mi.on("breakpoint-deleted", function(record)
+    if remove_gdb_breakpoint(record.results.id) then
+        M.save_to_disk()
     end
+end)
+
+-- Catch anything the notifications missed whenever the program stops
+mi.on("stopped", function()
+    M.sync()
+end)
+
+-- Where GDB put the breakpoints only holds for its session
+vim.api.nvim_create_autocmd("User", {
+    group = vim.api.nvim_create_augroup("RustTermdebugBreakpointResolution", { clear = true }),
+    pattern = "TermdebugStopPost",
+    callback = function()
+        for bufnr, infos in pairs(breakpoint_info) do
+            for extmark_id, info in pairs(infos) do
+                local unresolved = info.unresolved
+                info.unresolved = nil
+                info.address = nil
+                info.resolved_line = nil
+                if unresolved and vim.api.nvim_buf_is_valid(bufnr) then
+                    render_mark(bufnr, extmark_id)
+                end
+            end
+        end
+    end,
+    desc = "Forget where GDB resolved rust-termdebug breakpoints",
+})
+
+-- Version of the persistence file format
+-- 1: a bare array of breakpoints
+-- 2: { version, metadata = { locator, created_at }, breakpoints = [...] }
+--    Entries may name the locator that prepared their locator_data, overriding metadata.locator
+local SCHEMA_VERSION = 2
+
+-- Upgrades from each version to the next
+local MIGRATIONS = {
+    [1] = function(data)
+        for _, bp in ipairs(data.breakpoints) do
+            -- The first hash locator stored its hash outside locator_data
+            if bp.line_hash and not bp.locator_data then
+                bp.locator_data = { line_hash = bp.line_hash }
+            end
+            bp.line_hash = nil
 
-    local content = file:read("*a")
-    file:close()
+            -- Version 1 didn't record the locator; tell them apart by their data
+            if bp.locator_data and bp.locator_data.line_hash then
+                bp.locator = "hash"
+            elseif bp.locator_data and bp.locator_data.line_content then
+                bp.locator = "jaccard"
+            end
+        end
+        return { version = 2, metadata = {}, breakpoints = data.breakpoints }
+    end,
+}
+
+-- Decode a persistence file and migrate it to SCHEMA_VERSION
+-- Returns the envelope, or nil, the reason it couldn't be read and whether
+-- that's because a newer version of the plugin wrote it
+local function decode_persistence(content)
+    local ok, data = pcall(vim.json.decode, content)
+    if not ok or type(data) ~= "table" then
+        return nil, "invalid JSON"
+    end
 
-    if content == "" then
-        return
+    if data.version == nil then
+        -- A bare array (an empty one decodes the same as an empty object)
+        if next(data) ~= nil and data[1] == nil then
+            return nil, "unrecognized format"
+        end
+        data = { version = 1, breakpoints = data }
     end
 
-    local ok, breakpoints = pcall(vim.json.decode, content)
-    if not ok or not breakpoints then
+    if type(data.version) ~= "number" then
+        return nil, "unsupported version " .. tostring(data.version)
+    elseif data.version > SCHEMA_VERSION then
+        return nil, "saved by a newer version of rust-termdebug.nvim", true
+    end
+
+    while data.version < SCHEMA_VERSION do
+        data = MIGRATIONS[data.version](data)
+    end
+
+    if type(data.breakpoints) ~= "table" then
+        return nil, "missing breakpoints"
+    end
+    data.metadata = type(data.metadata) == "table" and data.metadata or {}
+    return data
+end
+
+-- Move an unreadable persistence file aside so saving doesn't overwrite it
+local function back_up_unreadable(path, reason)
+    local backup = string.format("%s.%s.bak", path, os.date("%Y%m%d%H%M%S"))
+    os.rename(path, backup)
+    vim.notify(
+        string.format("Could not load %s (%s); moved it to %s", short_path(path), reason, short_path(backup)),
+        vim.log.levels.WARN
+    )
+end
+
+-- Persistence files written by a newer version of the plugin, which this one
+-- leaves alone rather than overwrite in its older format: { [path] = true }
+local newer_files = {}
+
+-- Deal with a persistence file that couldn't be read: a corrupt one is moved
+-- aside, a newer one is kept and saving to it is turned off
+local function handle_unreadable(path, reason, newer)
+    if not newer then
+        back_up_unreadable(path, reason)
         return
     end
 
-    local strategy = line_locators.get(persistence_config.line_locator)
+    if not newer_files[path] then
+        newer_files[path] = true
+        vim.notify(
+            string.format(
+                "%s was %s; breakpoints won't be loaded from or saved to it until you update",
+                short_path(path),
+                reason
+            ),
+            vim.log.levels.WARN
+        )
+    end
+end
+
+-- Persistence entry for a tracked breakpoint: file, line, attributes, the line's
+-- text (to show if it can't be restored) and locator data
+-- save is shared by the entries of one save so locators can cache lookups
+local function make_entry(bufnr, extmark_id, line, strategy, save)
+    local entry = copy_info({
+        file = vim.api.nvim_buf_get_name(bufnr),
+        line = line + 1, -- Convert to 1-indexed
+    }, get_info(bufnr, extmark_id))
+
+    if not vim.api.nvim_buf_is_loaded(bufnr) then
+        vim.fn.bufload(bufnr)
+    end
+    local lines = vim.api.nvim_buf_get_lines(bufnr, line, line + 1, false)
+    if lines and #lines > 0 then
+        entry.text = vim.trim(lines[1])
+
+        -- Let strategy prepare additional data for persistence
+        if strategy and strategy.prepare then
+            local prepared = strategy.prepare(lines[1], bufnr, line, save or {})
+            if prepared then
+                entry.locator_data = prepared
+            end
+        end
+    end
+
+    return entry
+end
+
+-- Placements less certain than this are marked for the user to confirm
+local function min_confidence()
+    return persistence_config and persistence_config.min_confidence or 0.8
+end
+
+-- Create extmarks and function breakpoints for persistence entries
+-- Line breakpoints record which strategy placed them and how confidently
+-- Returns the number restored, messages for the ones that couldn't be placed,
+-- the placed line breakpoints as { file, line (1-indexed), info }, and the
+-- entries that couldn't be placed as { entry, reason }
+local function restore_entries(entries)
+    local configured = persistence_config and persistence_config.line_locator
     local restored_count = 0
     local skipped_messages = {}
+    local placed = {}
+    local failed = {}
+
+    local function skip(bp, bp_desc, reason)
+        table.insert(skipped_messages, string.format("breakpoint %s invalid: %s", bp_desc, reason))
+        table.insert(failed, { entry = bp, reason = reason })
+    end
+
+    -- Function breakpoints are restored by symbol; the rest need their lines located
+    local line_breakpoints = {}
+    for _, bp in ipairs(entries) do
+        if bp.kind == "function" then
+            if bp.symbol and not find_function(bp.symbol) then
+                table.insert(function_breakpoints, { symbol = bp.symbol, enabled = bp.enabled, group = bp.group })
+                restored_count = restored_count + 1
+            end
+        else
+            table.insert(line_breakpoints, bp)
+        end
+    end
 
     -- Create extmarks for each saved breakpoint
-    for _, bp in ipairs(breakpoints) do
+    for _, bp in ipairs(line_breakpoints) do
         local short_file = short_path(bp.file)
         local bp_desc = string.format("%s:%d", short_file, bp.line)
 
         -- Check if the file exists
         if vim.fn.filereadable(bp.file) ~= 1 then
-            table.insert(skipped_messages, string.format("breakpoint %s invalid: file missing", bp_desc))
+            skip(bp, bp_desc, "file missing")
         else
             -- Load the buffer if it's not already loaded
             local bufnr = vim.fn.bufnr(bp.file)
@@ -352,30 +1349,23 @@ M.load_from_disk = function()
             vim.fn.bufload(bufnr)
 
             local target_line = bp.line - 1 -- Convert to 0-indexed
-            local actual_line = nil
+            local actual_line, confidence, placed_by = nil, 1, nil
 
-            -- Use strategy to find the line
+            -- Use the strategy that saved the entry, so its locator data matches
+            local locator = bp.locator or configured
+            local strategy = line_locators.get(locator)
             if strategy and strategy.find then
-                -- For backwards compatibility, convert old format to new
-                local stored_data = bp.locator_data
-                if not stored_data and bp.line_hash then
-                    -- Old hash format
-                    stored_data = { line_hash = bp.line_hash }
-                end
-
-                actual_line = strategy.find(bufnr, stored_data, target_line)
+                actual_line, confidence, placed_by = strategy.find(bufnr, bp.locator_data, target_line)
+                -- Chains name the strategy in the chain that found the line
+                placed_by = placed_by or locator
                 if not actual_line then
-                    local reason = strategy.failure_reason or "location failed"
-                    table.insert(
-                        skipped_messages,
-                        string.format("breakpoint %s invalid: %s", bp_desc, reason)
-                    )
+                    skip(bp, bp_desc, strategy.failure_reason or "location failed")
                 end
             else
                 -- Fallback: exact matching
                 local line_count = vim.api.nvim_buf_line_count(bufnr)
                 if target_line >= line_count then
-                    table.insert(skipped_messages, string.format("breakpoint %s invalid: out of range", bp_desc))
+                    skip(bp, bp_desc, "out of range")
                 else
                     actual_line = target_line
                 end
@@ -383,27 +1373,50 @@ M.load_from_disk = function()
 
             -- Create the extmark if we found a valid line
             if actual_line then
-                -- Initialize buffer tracking if needed
-                if not breakpoint_marks[bufnr] then
-                    breakpoint_marks[bufnr] = {}
-                end
+                -- Create extmark, carrying over saved attributes
+                local info = copy_info({}, bp)
+                info.id = entry_id(bp)
+                info.placed_by = placed_by
+                info.confidence = confidence or 1
+                info.unconfirmed = info.confidence < min_confidence() or nil
+                place_mark(bufnr, actual_line, info)
+                table.insert(placed, { file = bp.file, line = actual_line + 1, info = info })
+                restored_count = restored_count + 1
+            end
+        end
+    end
 
-                -- Set up deletion handler for this buffer
-                setup_buffer_deletion_handler(bufnr)
+    return restored_count, skipped_messages, placed, failed
+end
 
-                -- Create extmark
-                local extmark_id = vim.api.nvim_buf_set_extmark(bufnr, ns_id, actual_line, 0, {
-                    sign_text = "●",
-                    sign_hl_group = "DiagnosticError",
-                })
+-- Keep breakpoints that couldn't be restored for review instead of dropping them
+local function orphan(failed)
+    for _, failure in ipairs(failed) do
+        local entry = vim.deepcopy(failure.entry)
+        entry.orphaned = true
+        entry.reason = failure.reason
+        table.insert(orphaned_breakpoints, entry)
+    end
+end
 
-                breakpoint_marks[bufnr][actual_line] = extmark_id
-                restored_count = restored_count + 1
-            end
+-- Report how many breakpoints were restored, which ones couldn't be placed,
+-- and which ones need checking because they were placed with low confidence
+local function report_restored(restored_count, skipped_messages, placed)
+    for _, bp in ipairs(placed or {}) do
+        if bp.info.unconfirmed then
+            vim.notify(
+                string.format(
+                    "breakpoint %s:%d placed by %s with %d%% confidence; check it and :RustDebugBreakConfirm",
+                    short_path(bp.file),
+                    bp.line,
+                    bp.info.placed_by,
+                    math.floor(bp.info.confidence * 100 + 0.5)
+                ),
+                vim.log.levels.WARN
+            )
         end
     end
 
-    -- Report results
     if #skipped_messages > 0 then
         for _, msg in ipairs(skipped_messages) do
             vim.notify(msg, vim.log.levels.WARN)
@@ -413,16 +1426,790 @@ M.load_from_disk = function()
     if restored_count > 0 or #skipped_messages > 0 then
         local msg = string.format("Restored %d breakpoint(s)", restored_count)
         if #skipped_messages > 0 then
-            msg = msg .. string.format(", %d failed", #skipped_messages)
+            msg = msg .. string.format(", %d failed (review them with :RustDebugOrphans)", #skipped_messages)
         end
         vim.notify(msg, vim.log.levels.INFO)
     end
 end
 
+-- Decode persistence file content into an envelope whose entries have absolute
+-- paths and name the locator that prepared them
+-- Returns nil and the reason if the content can't be read
+local function read_entries(content)
+    local data, err, newer = decode_persistence(content)
+    if not data then
+        return nil, err, newer
+    end
+
+    -- Older files have absolute paths; they load as is and are saved relative next time
+    local root = workspace.root()
+    for _, bp in ipairs(data.breakpoints) do
+        if bp.file then
+            bp.file = workspace.absolute_path(bp.file, root)
+        end
+        bp.locator = bp.locator or data.metadata.locator
+    end
+    return data
+end
+
+-- Key identifying a persistence entry, to match entries between editors
+-- Line breakpoints are matched by id, so one moved in another editor is still
+-- the same breakpoint
+local function entry_key(entry)
+    local prefix = entry.parked and "parked:" or entry.orphaned and "orphaned:" or ""
+    if entry.kind == "function" then
+        return prefix .. "fn:" .. entry.symbol
+    end
+    return prefix .. "bp:" .. entry_id(entry)
+end
+
+local function index_entries(entries)
+    local by_key = {}
+    for _, entry in ipairs(entries) do
+        by_key[entry_key(entry)] = entry
+    end
+    return by_key
+end
+
+-- Remember what's on disk, as the base for merging changes made by other editors
+local function set_synced(content, entries)
+    synced_content = content
+    synced_entries = index_entries(entries)
+end
+
+-- Apply changes another editor made to the persistence file (theirs) since we
+-- last read or wrote it (synced_entries) to our breakpoints
+local function apply_external(theirs_list)
+    -- Entries an older version saved without ids are the breakpoints at their location
+    for _, entry in ipairs(theirs_list) do
+        if not entry.id and entry.kind ~= "function" and not entry.parked and not entry.orphaned then
+            local bufnr, extmark_id = find_mark_at(entry.file, entry.line)
+            entry.id = bufnr and get_info(bufnr, extmark_id).id or nil
+        end
+    end
+
+    local theirs = index_entries(theirs_list)
+    local added = {}
+
+    for key, entry in pairs(theirs) do
+        local before = synced_entries[key]
+        if entry.parked then
+            if not before then
+                table.insert(parked_breakpoints, entry)
+            end
+        elseif entry.orphaned then
+            if not before then
+                table.insert(orphaned_breakpoints, entry)
+            end
+        elseif not before then
+            if entry.kind == "function" or not find_mark_at(entry.file, entry.line) then
+                table.insert(added, entry)
+            end
+        else
+            -- Attributes changed in the other editor
+            local changed = false
+            for _, field in ipairs(INFO_FIELDS) do
+                changed = changed or (field ~= "id" and before[field] ~= entry[field])
+            end
+            if changed and entry.kind == "function" then
+                local fb = find_function(entry.symbol)
+                if fb then
+                    fb.enabled = entry.enabled
+                    fb.group = entry.group
+                end
+            elseif entry.kind ~= "function" then
+                local bufnr, extmark_id = find_mark_by_id(entry_id(entry))
+                if bufnr and changed then
+                    local info = get_info(bufnr, extmark_id)
+                    copy_info(info, entry)
+                    info.id = entry_id(entry)
+                    render_mark(bufnr, extmark_id)
+                end
+                -- Moved in the other editor
+                if bufnr and entry.file == before.file and entry.line ~= before.line then
+                    local info = get_info(bufnr, extmark_id)
+                    local old_line = line_of_extmark(bufnr, extmark_id)
+                    if move_mark(bufnr, extmark_id, entry.line - 1) and mi.is_active() then
+                        pcall(send_delete, bufnr, old_line, info)
+                        release_number(info)
+                        pcall(send_break, entry.file, entry.line, info)
+                    end
+                end
+            end
+        end
+    end
+
+    for key, entry in pairs(synced_entries) do
+        if not theirs[key] then
+            -- Deleted in the other editor
+            if entry.parked or entry.orphaned then
+                local list = entry.parked and parked_breakpoints or orphaned_breakpoints
+                for i, kept in ipairs(list) do
+                    if entry_key(kept) == key then
+                        table.remove(list, i)
+                        break
+                    end
+                end
+            elseif entry.kind == "function" then
+                local fb, index = find_function(entry.symbol)
+                if fb then
+                    table.remove(function_breakpoints, index)
+                    if fb.number then
+                        gdb_breakpoints[fb.number] = nil
+                        pcall(vim.fn.TermDebugSendCommand, "delete " .. fb.number)
+                    end
+                end
+            else
+                local bufnr, extmark_id = find_mark_by_id(entry_id(entry))
+                local line = bufnr and line_of_extmark(bufnr, extmark_id)
+                if line then
+                    if mi.is_active() then
+                        pcall(send_delete, bufnr, line, get_info(bufnr, extmark_id))
+                    end
+                    forget_mark(bufnr, line)
+                    vim.api.nvim_buf_del_extmark(bufnr, ns_id, extmark_id)
+                end
+            end
+        end
+    end
+
+    -- Breakpoints added in the other editor; files that aren't here are skipped quietly
+    local _, _, placed = restore_entries(added)
+    if mi.is_active() then
+        for _, bp in ipairs(placed) do
+            pcall(send_break, bp.file, bp.line, bp.info)
+        end
+        for _, entry in ipairs(added) do
+            if entry.kind == "function" then
+                pcall(vim.fn.TermDebugSendCommand, "break " .. entry.symbol)
+            end
+        end
+    end
+end
+
+-- Read a file straight from disk, ignoring our own pending writes
+local function read_disk(path)
+    local file = io.open(path, "r")
+    if not file then
+        return nil
+    end
+    local content = file:read("*a")
+    file:close()
+    return content
+end
+
+-- Merge changes other editors made to the persistence file since we last synced
+-- Returns true if the file changed
+local function merge_external(path)
+    local content = read_disk(path)
+    if not content or content == "" or content == synced_content or vim.tbl_contains(own_writes, content) then
+        return false
+    end
+
+    local data, err, newer = read_entries(content)
+    if not data then
+        handle_unreadable(path, err, newer)
+        return false
+    end
+
+    -- Before anything was loaded synced_entries is empty, so everything on disk is new to us
+    apply_external(data.breakpoints)
+    file_created_at = file_created_at or data.metadata.created_at
+    set_synced(content, data.breakpoints)
+    return true
+end
+
+-- Build the persistence file content from our breakpoints, merging in changes
+-- other editors saved since we last synced
+local function serialize(path)
+    merge_external(path)
+    if newer_files[path] then
+        return nil
+    end
+
+    local breakpoints_to_save = {}
+    local strategy = line_locators.get(persistence_config.line_locator)
+    local save = {}
+
+    -- Collect all breakpoint locations from extmarks
+    for bufnr, marks in pairs(breakpoint_marks) do
+        -- Only save breakpoints for named buffers (files on disk)
+        if vim.api.nvim_buf_is_valid(bufnr) and vim.api.nvim_buf_get_name(bufnr) ~= "" then
+            for _, extmark_id in pairs(marks) do
+                local mark = vim.api.nvim_buf_get_extmark_by_id(bufnr, ns_id, extmark_id, {})
+                -- Temporary (tbreak) breakpoints only last for this debug session
+                if mark and #mark > 0 and not get_info(bufnr, extmark_id).temporary then
+                    table.insert(breakpoints_to_save, make_entry(bufnr, extmark_id, mark[1], strategy, save))
+                end
+            end
+        end
+    end
+
+    -- Function breakpoints are saved by symbol, independent of file and line
+    for _, fb in ipairs(function_breakpoints) do
+        table.insert(breakpoints_to_save, {
+            kind = "function",
+            symbol = fb.symbol,
+            enabled = fb.enabled,
+            group = fb.group,
+        })
+    end
+
+    -- Parked groups are saved as they were when the group was deleted
+    for _, entry in ipairs(parked_breakpoints) do
+        table.insert(breakpoints_to_save, vim.deepcopy(entry))
+    end
+
+    -- So are breakpoints that couldn't be restored, until they're reviewed
+    for _, entry in ipairs(orphaned_breakpoints) do
+        table.insert(breakpoints_to_save, vim.deepcopy(entry))
+    end
+    local synced = vim.deepcopy(breakpoints_to_save)
+
+    -- Store paths relative to the workspace root so the file works in other checkouts
+    local root = workspace.root()
+    for _, entry in ipairs(breakpoints_to_save) do
+        if entry.file then
+            entry.file = workspace.relative_path(entry.file, root)
+        end
+    end
+
+    file_created_at = file_created_at or os.date("!%Y-%m-%dT%H:%M:%SZ")
+    local content = vim.json.encode({
+        version = SCHEMA_VERSION,
+        metadata = {
+            locator = persistence_config.line_locator,
+            created_at = file_created_at,
+        },
+        breakpoints = breakpoints_to_save,
+    })
+    set_synced(content, synced)
+    table.insert(own_writes, content)
+    if #own_writes > 5 then
+        table.remove(own_writes, 1)
+    end
+    return content
+end
+
+-- Save breakpoints to disk for persistence across sessions
+-- The write is debounced and asynchronous unless opts.sync is set (eg on exit).
+-- Changes another editor saved in the meantime are merged in just before writing.
+M.save_to_disk = function(opts)
+    if not persistence_config then
+        return
+    end
+
+    local path = get_persistence_file()
+    if newer_files[path] then
+        return
+    end
+    local content = function()
+        -- Persistence may have been turned off before a debounced write ran
+        return persistence_config and serialize(path)
+    end
+
+    if opts and opts.sync then
+        state_file.write_sync(path, content)
+    else
+        state_file.write(path, content)
+    end
+end
+
+-- Pick up breakpoints another editor saved to the persistence file
+M.sync_from_disk = function()
+    if persistence_config then
+        merge_external(get_persistence_file())
+    end
+end
+
+-- Watch the persistence file so breakpoints saved by other editors show up here
+M.watch_file = function()
+    if file_watcher then
+        file_watcher:stop()
+        file_watcher = nil
+    end
+    if not persistence_config then
+        return
+    end
+
+    -- Saves rename a temp file over the persistence file, so watch its directory
+    local path = get_persistence_file()
+    local name = vim.fn.fnamemodify(path, ":t")
+    file_watcher = (vim.uv or vim.loop).new_fs_event()
+    file_watcher:start(vim.fn.fnamemodify(path, ":h"), {}, function(err, filename)
+        if not err and filename == name then
+            vim.schedule(M.sync_from_disk)
+        end
+    end)
+end
+
+-- Load breakpoints from disk and create extmarks
+M.load_from_disk = function()
+    if not persistence_config then
+        return
+    end
+
+    local path = get_persistence_file()
+    local content = state_file.read(path)
+    if not content and active_branch then
+        -- First time on this branch with per-branch persistence; start from the shared set
+        path = workspace.state_dir() .. "/breakpoints.json"
+        content = state_file.read(path)
+    end
+
+    if not content or content == "" then
+        return
+    end
+
+    local data, err, newer = read_entries(content)
+    if not data then
+        handle_unreadable(path, err, newer)
+        return
+    end
+    file_created_at = data.metadata.created_at
+    set_synced(content, data.breakpoints)
+
+    -- Parked groups stay in the file until they're restored
+    local active, orphans = {}, {}
+    for _, bp in ipairs(data.breakpoints) do
+        if bp.parked then
+            table.insert(parked_breakpoints, bp)
+        elseif bp.orphaned then
+            local copy = vim.deepcopy(bp)
+            copy.orphaned = nil
+            copy.reason = nil
+            table.insert(orphans, copy)
+        else
+            table.insert(active, bp)
+        end
+    end
+
+    local restored_count, skipped_messages, placed, failed = restore_entries(active)
+    orphan(failed)
+
+    -- Orphans are tried again quietly, eg in case their file came back
+    local reattached, _, reattached_placed, still_failed = restore_entries(orphans)
+    orphan(still_failed)
+
+    report_restored(restored_count + reattached, skipped_messages, vim.list_extend(placed, reattached_placed))
+end
+
+-- Breakpoints that couldn't be restored: persistence entries with the reason and,
+-- when it was saved, the text of the line they were on
+M.get_orphans = function()
+    return vim.deepcopy(orphaned_breakpoints)
+end
+
+-- Find a tracked orphan matching one returned by get_orphans; returns its index
+local function find_orphan(orphan)
+    local key = entry_key(orphan)
+    for i, entry in ipairs(orphaned_breakpoints) do
+        if entry_key(entry) == key then
+            return i
+        end
+    end
+    return nil
+end
+
+-- Lines of an orphan's file that look most like the line it was saved on, best first
+-- Returns up to limit (default 5) { line (1-indexed), text, score } tables
+M.orphan_candidates = function(orphan, limit)
+    if not orphan.text or not orphan.file or vim.fn.filereadable(orphan.file) ~= 1 then
+        return {}
+    end
+
+    local bufnr = vim.fn.bufnr(orphan.file)
+    local lines = (bufnr ~= -1 and vim.api.nvim_buf_is_loaded(bufnr)) and vim.api.nvim_buf_get_lines(bufnr, 0, -1, false)
+        or vim.fn.readfile(orphan.file)
+
+    local jaccard = line_locators.get("jaccard")
+    local target = jaccard.tokenize(orphan.text)
+    local candidates = {}
+    for i, text in ipairs(lines) do
+        local score = jaccard.similarity(target, jaccard.tokenize(text))
+        if score > 0 then
+            table.insert(candidates, { line = i, text = vim.trim(text), score = score })
+        end
+    end
+
+    -- Most similar first, then nearest to where it was
+    table.sort(candidates, function(a, b)
+        if a.score ~= b.score then
+            return a.score > b.score
+        end
+        return math.abs(a.line - orphan.line) < math.abs(b.line - orphan.line)
+    end)
+    for i = #candidates, (limit or 5) + 1, -1 do
+        candidates[i] = nil
+    end
+    return candidates
+end
+
+-- Place an orphan on a line (1-indexed) of its file and stop tracking it as an orphan
+M.reanchor_orphan = function(orphan, line)
+    local index = find_orphan(orphan)
+    if not index or vim.fn.filereadable(orphan.file) ~= 1 then
+        return false
+    end
+
+    local bufnr = vim.fn.bufnr(orphan.file)
+    if bufnr == -1 then
+        bufnr = vim.fn.bufadd(orphan.file)
+    end
+    vim.fn.bufload(bufnr)
+
+    -- Don't replace a breakpoint that's already on the line; the orphan stays
+    -- in the list to be re-anchored somewhere else
+    if breakpoint_marks[bufnr] and breakpoint_marks[bufnr][line - 1] then
+        vim.notify(string.format("Line %d already has a breakpoint", line), vim.log.levels.WARN)
+        return false
+    end
+    local entry = table.remove(orphaned_breakpoints, index)
+
+    local info = copy_info({}, entry)
+    place_mark(bufnr, line - 1, info)
+
+    -- Try to create the actual GDB breakpoint if termdebug is running
+    pcall(send_break, entry.file, line, info)
+
+    -- Save to disk immediately
+    M.save_to_disk()
+    return true
+end
+
+-- Forget an orphan for good
+M.discard_orphan = function(orphan)
+    local index = find_orphan(orphan)
+    if not index then
+        return false
+    end
+    table.remove(orphaned_breakpoints, index)
+
+    -- Save to disk immediately
+    M.save_to_disk()
+    return true
+end
+
+-- Names of all breakpoint groups, active or parked, sorted
+M.get_groups = function()
+    local seen = {}
+    for _, infos in pairs(breakpoint_info) do
+        for _, info in pairs(infos) do
+            if info.group then
+                seen[info.group] = true
+            end
+        end
+    end
+    for _, fb in ipairs(function_breakpoints) do
+        if fb.group then
+            seen[fb.group] = true
+        end
+    end
+    for _, entry in ipairs(parked_breakpoints) do
+        seen[entry.group] = true
+    end
+
+    local names = vim.tbl_keys(seen)
+    table.sort(names)
+    return names
+end
+
+-- Call fn(bufnr, line, extmark_id, info) for each line breakpoint in a group
+local function each_in_group(name, fn)
+    for bufnr, marks in pairs(vim.deepcopy(breakpoint_marks)) do
+        for line, extmark_id in pairs(marks) do
+            local info = get_info(bufnr, extmark_id)
+            if info.group == name then
+                fn(bufnr, line, extmark_id, info)
+            end
+        end
+    end
+end
+
+-- Enable or disable every breakpoint in a group
+local function set_group_enabled(name, enabled)
+    local count = 0
+    each_in_group(name, function(bufnr, line)
+        M.set_enabled_at(bufnr, line, enabled)
+        count = count + 1
+    end)
+    for _, fb in ipairs(function_breakpoints) do
+        if fb.group == name then
+            M.set_function_enabled(fb.symbol, enabled)
+            count = count + 1
+        end
+    end
+    return count
+end
+
+M.enable_group = function(name)
+    local count = set_group_enabled(name, true)
+    vim.notify(string.format("Enabled %d breakpoint(s) in %s", count, name), vim.log.levels.INFO)
+end
+
+M.disable_group = function(name)
+    local count = set_group_enabled(name, false)
+    vim.notify(string.format("Disabled %d breakpoint(s) in %s", count, name), vim.log.levels.INFO)
+end
+
+-- Delete a group's breakpoints from the editor and GDB, parking them in the
+-- persistence file so the group can be restored later
+-- Deleting a group that's already parked forgets it for good
+M.delete_group = function(name)
+    local strategy = persistence_config and line_locators.get(persistence_config.line_locator)
+    local save = {}
+    local count = 0
+
+    each_in_group(name, function(bufnr, line, extmark_id, info)
+        local mark = vim.api.nvim_buf_get_extmark_by_id(bufnr, ns_id, extmark_id, {})
+        if mark and #mark > 0 and not info.temporary and vim.api.nvim_buf_get_name(bufnr) ~= "" then
+            local entry = make_entry(bufnr, extmark_id, mark[1], strategy, save)
+            entry.locator = persistence_config and persistence_config.line_locator
+            entry.parked = true
+            table.insert(parked_breakpoints, entry)
+        end
+
+        pcall(send_delete, bufnr, line, info)
+        forget_mark(bufnr, line)
+        vim.api.nvim_buf_del_extmark(bufnr, ns_id, extmark_id)
+        count = count + 1
+    end)
+
+    for i = #function_breakpoints, 1, -1 do
+        local fb = function_breakpoints[i]
+        if fb.group == name then
+            table.insert(parked_breakpoints, {
+                kind = "function",
+                symbol = fb.symbol,
+                enabled = fb.enabled,
+                group = fb.group,
+                parked = true,
+            })
+            M.delete_function(fb.symbol)
+            count = count + 1
+        end
+    end
+
+    if count == 0 then
+        -- Nothing active in the group; forget the parked breakpoints
+        local kept = {}
+        for _, entry in ipairs(parked_breakpoints) do
+            if entry.group ~= name then
+                table.insert(kept, entry)
+            end
+        end
+        count = #parked_breakpoints - #kept
+        parked_breakpoints = kept
+        vim.notify(string.format("Forgot %d parked breakpoint(s) in %s", count, name), vim.log.levels.INFO)
+    else
+        vim.notify(
+            string.format("Parked %d breakpoint(s) in %s; restore the group to bring them back", count, name),
+            vim.log.levels.INFO
+        )
+    end
+
+    -- Save to disk immediately
+    M.save_to_disk()
+end
+
+-- Bring back a parked group's breakpoints, in the editor and in GDB
+M.restore_group = function(name)
+    local entries, kept = {}, {}
+    for _, entry in ipairs(parked_breakpoints) do
+        if entry.group == name then
+            local copy = vim.deepcopy(entry)
+            copy.parked = nil
+            table.insert(entries, copy)
+        else
+            table.insert(kept, entry)
+        end
+    end
+
+    if #entries == 0 then
+        vim.notify("No parked breakpoints in " .. name, vim.log.levels.INFO)
+        return
+    end
+    parked_breakpoints = kept
+
+    local restored_count, skipped_messages, placed, failed = restore_entries(entries)
+    orphan(failed)
+
+    -- Try to create the actual GDB breakpoints if termdebug is running
+    for _, bp in ipairs(placed) do
+        pcall(send_break, bp.file, bp.line, bp.info)
+    end
+    for _, entry in ipairs(entries) do
+        if entry.kind == "function" then
+            pcall(vim.fn.TermDebugSendCommand, "break " .. entry.symbol)
+            if entry.enabled == false then
+                pcall(vim.fn.TermDebugSendCommand, "disable $bpnum")
+            end
+        end
+    end
+
+    report_restored(restored_count, skipped_messages, placed)
+
+    -- Save to disk immediately
+    M.save_to_disk()
+end
+
+-- Put the breakpoint on a line (0-indexed) in a group; nil or "" removes it from its group
+M.set_group_at = function(bufnr, line, name)
+    local extmark_id = breakpoint_marks[bufnr] and breakpoint_marks[bufnr][line]
+    if not extmark_id then
+        return false
+    end
+
+    get_info(bufnr, extmark_id).group = (name ~= "" and name) or nil
+
+    -- Save to disk immediately
+    M.save_to_disk()
+    return true
+end
+
+-- Put a function breakpoint in a group; nil or "" removes it from its group
+M.set_function_group = function(symbol, name)
+    local fb = find_function(symbol)
+    if not fb then
+        return false
+    end
+
+    fb.group = (name ~= "" and name) or nil
+
+    -- Save to disk immediately
+    M.save_to_disk()
+    return true
+end
+
+-- Ask for a group name for the breakpoint on the current line
+M.group_curline = function()
+    local bufnr = vim.api.nvim_get_current_buf()
+    local line = vim.api.nvim_win_get_cursor(0)[1] - 1 -- 0-indexed
+    local existing = breakpoint_marks[bufnr] and breakpoint_marks[bufnr][line]
+    if not existing then
+        vim.notify("No breakpoint on this line", vim.log.levels.INFO)
+        return
+    end
+
+    vim.ui.input({
+        prompt = "Breakpoint group: ",
+        default = get_info(bufnr, existing).group or "",
+    }, function(name)
+        if name then
+            M.set_group_at(bufnr, line, vim.trim(name))
+        end
+    end)
+end
+
 -- Enable or disable breakpoint persistence
 -- @param config table|nil - persistence config with line_locator, or nil to disable
 M.set_persistence = function(config)
     persistence_config = config
+    line_locators.configure(config)
+    active_branch = config and config.per_branch and workspace.branch() or nil
+end
+
+-- Switch to the current git branch's breakpoint set if the branch changed
+-- The old set is saved first; a branch without a saved set keeps the current breakpoints
+M.check_branch = function()
+    if not (persistence_config and persistence_config.per_branch) then
+        return
+    end
+
+    local branch = workspace.branch()
+    if not branch or branch == active_branch then
+        return
+    end
+
+    -- Synchronously, so a pending write can't save the new branch's set to the old file
+    M.save_to_disk({ sync = true })
+    local previous = active_branch
+    active_branch = branch
+    file_created_at = nil
+    synced_content = nil
+    synced_entries = {}
+    if file_watcher then
+        M.watch_file()
+    end
+
+    if not state_file.exists(get_persistence_file()) then
+        M.save_to_disk()
+        return
+    end
+
+    -- Remove the old branch's breakpoints from GDB if termdebug is running
+    local debugging = mi.is_active()
+    if debugging then
+        for bufnr, marks in pairs(breakpoint_marks) do
+            for line, extmark_id in pairs(marks) do
+                pcall(send_delete, bufnr, line, get_info(bufnr, extmark_id))
+            end
+        end
+        for _, fb in ipairs(function_breakpoints) do
+            pcall(vim.fn.TermDebugSendCommand, fb.number and ("delete " .. fb.number) or ("clear " .. fb.symbol))
+        end
+    end
+
+    clear_tracked()
+    parked_breakpoints = {}
+    orphaned_breakpoints = {}
+    vim.notify(
+        string.format("Switching breakpoints from branch %s to %s", previous or "?", branch),
+        vim.log.levels.INFO
+    )
+    M.load_from_disk()
+
+    if debugging then
+        M.restore_all()
+    end
+end
+
+-- Notice branch switches: on focus and directory changes, and when .git/HEAD changes
+M.watch_branch = function()
+    local uv = vim.uv or vim.loop
+    local watcher = nil
+
+    local function watch_head()
+        if watcher then
+            watcher:stop()
+            watcher = nil
+        end
+
+        local git_dir = workspace.git_dir()
+        if not git_dir then
+            return
+        end
+
+        -- Git replaces HEAD by renaming a new file over it, so watch the directory
+        watcher = uv.new_fs_event()
+        watcher:start(git_dir, {}, function(err, filename)
+            if not err and filename == "HEAD" then
+                vim.schedule(M.check_branch)
+            end
+        end)
+    end
+
+    local augroup = vim.api.nvim_create_augroup("RustTermdebugBranch", { clear = true })
+    vim.api.nvim_create_autocmd("FocusGained", {
+        group = augroup,
+        callback = function()
+            M.check_branch()
+        end,
+        desc = "Switch rust-termdebug breakpoints when the git branch changes",
+    })
+    vim.api.nvim_create_autocmd("DirChanged", {
+        group = augroup,
+        callback = function()
+            watch_head()
+            M.check_branch()
+        end,
+        desc = "Switch rust-termdebug breakpoints when the git branch changes",
+    })
+
+    watch_head()
 end
 
+-- Extmark sign options (sign_text, sign_hl_group) for a breakpoint from get_all()
+M.sign_opts = sign_opts
+
 return M
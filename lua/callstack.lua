@@ -0,0 +1,320 @@
+-- Call stack of the stopped program, read over the GDB/MI channel
+-- Highlights the selected frame's line and marks the caller frames' lines in
+-- open source buffers, and moves between frames with RustDebugUp/RustDebugDown
+
+local mi = require("mi")
+
+local callstack = {}
+
+-- Namespace for the frame highlights
+local ns_id = vim.api.nvim_create_namespace("rust_termdebug_callstack")
+
+vim.api.nvim_set_hl(0, "RustTermdebugCurrentFrame", { link = "CursorLine", default = true })
+vim.api.nvim_set_hl(0, "RustTermdebugCallerFrame", { link = "Comment", default = true })
+
+-- Frames of the stopped thread, innermost first:
+-- { { level = number, func = string?, file = string?, line = number?, addr = string? } }
+-- file and line are missing for frames without debug info, eg in libc
+local frames = {}
+
+-- Level of the frame GDB has selected
+local selected = 0
+
+-- Escapes in legacy mangled Rust symbols
+local ESCAPES = {
+    SP = "@",
+    BP = "*",
+    RF = "&",
+    LT = "<",
+    GT = ">",
+    LP = "(",
+    RP = ")",
+    C = ",",
+}
+
+-- Readable Rust path for a function name from GDB
+-- GDB demangles most names itself, but leaves legacy _ZN symbols of frames
+-- without debug info, and keeps the ::h0123456789abcdef hash suffix
+callstack.demangle = function(name)
+    if not name then
+        return nil
+    end
+
+    local mangled = name:match("^_?_ZN(.*)E$")
+    if mangled then
+        local segments = {}
+        local i = 1
+        while i <= #mangled do
+            local len, rest = mangled:match("^(%d+)()", i)
+            if not len then
+                break
+            end
+            table.insert(segments, mangled:sub(rest, rest + tonumber(len) - 1))
+            i = rest + tonumber(len)
+        end
+        if #segments > 0 then
+            name = table.concat(segments, "::")
+        end
+    end
+
+    name = name:gsub("::h%x+$", "")
+    -- Segments starting with an escape get a leading underscore
+    name = name:gsub("^_%$", "$"):gsub("::_%$", "::$")
+    name = name:gsub("%$(%w+)%$", function(escape)
+        local code = escape:match("^u(%x+)$")
+        if code then
+            return string.char(tonumber(code, 16))
+        end
+        return ESCAPES[escape]
+    end)
+    name = name:gsub("%.%.", "::")
+    return name
+end
+
+-- Path fragments of sources that aren't part of the workspace
+local LIBRARY_PATHS = {
+    "^/rustc/",
+    "/%.rustup/toolchains/",
+    "/%.cargo/registry/",
+    "/%.cargo/git/",
+    "/library/std/src/",
+    "/library/core/src/",
+    "/library/alloc/src/",
+}
+
+-- Whether a frame is in std, core or an external crate rather than the
+-- workspace; frames without source are counted as library code
+callstack.is_library = function(frame)
+    if not frame.file then
+        return true
+    end
+    for _, pattern in ipairs(LIBRARY_PATHS) do
+        if frame.file:find(pattern) then
+            return true
+        end
+    end
+    return false
+end
+
+-- Frame from an MI frame tuple
+callstack.parse_frame = function(frame)
+    return {
+        level = tonumber(frame.level) or 0,
+        func = callstack.demangle(frame.func),
+        file = frame.fullname,
+        line = tonumber(frame.line),
+        addr = frame.addr,
+    }
+end
+
+local function clear_highlights()
+    for _, bufnr in ipairs(vim.api.nvim_list_bufs()) do
+        if vim.api.nvim_buf_is_loaded(bufnr) then
+            vim.api.nvim_buf_clear_namespace(bufnr, ns_id, 0, -1)
+        end
+    end
+end
+
+-- Redraw the frame highlights in the loaded buffers
+local function render()
+    clear_highlights()
+
+    for _, frame in ipairs(frames) do
+        local bufnr = frame.file and vim.fn.bufnr(frame.file) or -1
+        if
+            bufnr ~= -1
+            and vim.api.nvim_buf_is_loaded(bufnr)
+            and frame.line
+            and frame.line <= vim.api.nvim_buf_line_count(bufnr)
+        then
+            local opts
+            if frame.level == selected then
+                opts = { line_hl_group = "RustTermdebugCurrentFrame" }
+            else
+                opts = {
+                    virt_text = { { string.format("◂ #%d %s", frame.level, frame.func or "??"), "RustTermdebugCallerFrame" } },
+                    virt_text_pos = "eol",
+                }
+            end
+            vim.api.nvim_buf_set_extmark(bufnr, ns_id, frame.line - 1, 0, opts)
+        end
+    end
+end
+
+-- Forget the stack, eg when the program continues or the session ends
+callstack.clear = function()
+    frames = {}
+    selected = 0
+    clear_highlights()
+end
+
+-- Replace the stack with GDB's frame list (MI frame tuples, innermost first)
+callstack.update = function(mi_frames)
+    frames = {}
+    for _, frame in ipairs(mi_frames) do
+        table.insert(frames, callstack.parse_frame(frame))
+    end
+    render()
+end
+
+-- Ask GDB for the stopped thread's frames; on_done runs once they're read
+-- Returns false if the MI channel isn't active
+callstack.refresh = function(on_done)
+    return mi.send("-stack-list-frames", function(record)
+        if record.class == "done" and record.results.stack then
+            callstack.update(record.results.stack)
+        end
+        if on_done then
+            on_done()
+        end
+    end)
+end
+
+-- Frames of the stopped thread, innermost first
+callstack.get_frames = function()
+    return vim.deepcopy(frames)
+end
+
+-- The selected frame, or nil when the program isn't stopped
+callstack.get_selected = function()
+    for _, frame in ipairs(frames) do
+        if frame.level == selected then
+            return vim.deepcopy(frame)
+        end
+    end
+    return nil
+end
+
+-- A window showing a source file rather than the GDB or program terminals
+local function source_window()
+    if vim.bo.buftype == "" then
+        return vim.api.nvim_get_current_win()
+    end
+    for _, win in ipairs(vim.api.nvim_tabpage_list_wins(0)) do
+        if vim.bo[vim.api.nvim_win_get_buf(win)].buftype == "" then
+            return win
+        end
+    end
+    return nil
+end
+
+-- Show a frame's line in a source window
+local function jump_to(frame)
+    if not frame.file or not frame.line or vim.fn.filereadable(frame.file) ~= 1 then
+        vim.notify(string.format("No source for frame #%d (%s)", frame.level, frame.func or "??"), vim.log.levels.INFO)
+        return
+    end
+
+    local win = source_window()
+    if win then
+        vim.api.nvim_set_current_win(win)
+    end
+    vim.cmd("edit " .. vim.fn.fnameescape(frame.file))
+    vim.api.nvim_win_set_cursor(0, { frame.line, 0 })
+    vim.cmd("normal! zz")
+end
+
+-- Select a frame by level in GDB and show it
+callstack.select = function(level)
+    local frame = nil
+    for _, candidate in ipairs(frames) do
+        if candidate.level == level then
+            frame = candidate
+        end
+    end
+    if not frame then
+        return false
+    end
+
+    selected = level
+    pcall(vim.fn.TermDebugSendCommand, "frame " .. level)
+    render()
+    jump_to(frame)
+    return true
+end
+
+-- Select the frame count levels towards the callers, stopping at the outermost
+callstack.up = function(count)
+    if #frames == 0 then
+        vim.notify("The program isn't stopped", vim.log.levels.INFO)
+        return
+    end
+
+    local outermost = frames[#frames].level
+    if selected >= outermost then
+        vim.notify("Already at the outermost frame", vim.log.levels.INFO)
+        return
+    end
+    callstack.select(math.min(selected + (count or 1), outermost))
+end
+
+-- Select the frame count levels towards the innermost frame, stopping at it
+callstack.down = function(count)
+    if #frames == 0 then
+        vim.notify("The program isn't stopped", vim.log.levels.INFO)
+        return
+    end
+
+    local innermost = frames[1].level
+    if selected <= innermost then
+        vim.notify("Already at the innermost frame", vim.log.levels.INFO)
+        return
+    end
+    callstack.select(math.max(selected - (count or 1), innermost))
+end
+
+-- The program stopped: the innermost frame is selected until the stack is read
+mi.on("stopped", function(record)
+    selected = 0
+    if record.results.frame then
+        callstack.update({ record.results.frame })
+    end
+    callstack.refresh()
+end)
+
+mi.on("running", function()
+    callstack.clear()
+end)
+
+-- Frames selected in the GDB REPL (up, down, frame N) or another thread
+mi.on("thread-selected", function(record)
+    local frame = record.results.frame
+    if not frame then
+        return
+    end
+
+    local level = tonumber(frame.level) or 0
+    local known = false
+    for _, candidate in ipairs(frames) do
+        known = known or (candidate.level == level and candidate.addr == frame.addr)
+    end
+    selected = level
+    if known then
+        render()
+    else
+        -- Another thread's stack
+        callstack.refresh()
+    end
+end)
+
+local augroup = vim.api.nvim_create_augroup("RustTermdebugCallstack", { clear = true })
+
+-- Highlight frames in source files opened while stopped
+vim.api.nvim_create_autocmd("BufWinEnter", {
+    group = augroup,
+    callback = function()
+        if #frames > 0 then
+            render()
+        end
+    end,
+    desc = "Highlight rust-termdebug call stack frames",
+})
+
+vim.api.nvim_create_autocmd("User", {
+    group = augroup,
+    pattern = "TermdebugStopPost",
+    callback = callstack.clear,
+    desc = "Clear rust-termdebug call stack highlights",
+})
+
+return callstack
@@ -1,6 +1,9 @@
 local termdebug = require("termdebug")
 local breakpoints = require("breakpoints")
 local options = require("options")
+local panics = require("panics")
+local scheduler = require("scheduler")
+local watchpoints = require("watchpoints")
 
 local cargo = {}
 
@@ -46,6 +49,20 @@ local function get_available_profiles(workspace_root)
     return profiles
 end
 
+-- Recreate breakpoints and other debugger state after reloading the binary
+local function restore_session_state()
+    -- Delete all GDB breakpoints
+    vim.fn.TermDebugSendCommand("d")
+    -- Restore breakpoints from extmarks
+    breakpoints.restore_all()
+    -- Re-arm watchpoints; locals stay pending until they're back in scope
+    watchpoints.restore_all()
+    -- Re-arm panic breakpoints if enabled for this workspace
+    panics.restore()
+    -- Pin the scheduler again if it was pinned in this session
+    scheduler.restore()
+end
+
 -- Debug session types
 local DebugType = {
     BINARY = "binary",
@@ -821,10 +838,7 @@ cargo.rebuild_and_reload = function()
             vim.notify("Reloading binary: " .. session.path, vim.log.levels.INFO)
             -- Send file command to reload the binary
             vim.fn.TermDebugSendCommand("file " .. session.path)
-            -- Delete all GDB breakpoints
-            vim.fn.TermDebugSendCommand("d")
-            -- Restore breakpoints from extmarks
-            breakpoints.restore_all()
+            restore_session_state()
         end)
     elseif session.type == DebugType.TEST then
         -- For tests, rebuild all tests
@@ -839,8 +853,7 @@ cargo.rebuild_and_reload = function()
                 if artifact.name == session.name then
                     vim.notify("Reloading test: " .. artifact.path, vim.log.levels.INFO)
                     vim.fn.TermDebugSendCommand("file " .. artifact.path)
-                    vim.fn.TermDebugSendCommand("d")
-                    breakpoints.restore_all()
+                    restore_session_state()
                     return
                 end
             end
@@ -853,8 +866,7 @@ cargo.rebuild_and_reload = function()
         run_build(session.build_cmd, function()
             vim.notify("Reloading example: " .. session.path, vim.log.levels.INFO)
             vim.fn.TermDebugSendCommand("file " .. session.path)
-            vim.fn.TermDebugSendCommand("d")
-            breakpoints.restore_all()
+            restore_session_state()
         end)
     elseif session.type == DebugType.BENCH then
         -- For benchmarks, rebuild all benchmarks
@@ -869,8 +881,7 @@ cargo.rebuild_and_reload = function()
                 if artifact.name == session.name then
                     vim.notify("Reloading benchmark: " .. artifact.path, vim.log.levels.INFO)
                     vim.fn.TermDebugSendCommand("file " .. artifact.path)
-                    vim.fn.TermDebugSendCommand("d")
-                    breakpoints.restore_all()
+                    restore_session_state()
                     return
                 end
             end
@@ -1,7 +1,10 @@
 local breakpoints = require("breakpoints")
+local callstack = require("callstack")
 local scheduler = require("scheduler")
 local cargo = require("cargo")
 local termdebug = require("termdebug")
+local panics = require("panics")
+local watchpoints = require("watchpoints")
 
 local commands = {}
 
@@ -22,6 +25,121 @@ commands.create = function()
         desc = "Set a breakpoint at current line; same as :Break",
     })
 
+    vim.api.nvim_create_user_command("RustDebugBreakCondition", breakpoints.create_conditional, {
+        desc = "Set a conditional breakpoint at current line",
+    })
+
+    vim.api.nvim_create_user_command("RustDebugLogpoint", breakpoints.create_logpoint, {
+        desc = "Set a logpoint (dprintf) at current line",
+    })
+
+    vim.api.nvim_create_user_command("RustDebugBreakHitCount", breakpoints.create_hit_count, {
+        desc = "Set a breakpoint at current line that stops on the Nth hit",
+    })
+
+    vim.api.nvim_create_user_command("RustDebugBreakFunction", function(opts)
+        if opts.args ~= "" then
+            breakpoints.add_function(opts.args)
+        else
+            breakpoints.create_function()
+        end
+    end, {
+        nargs = "?",
+        desc = "Set a breakpoint on a function symbol; pick one if no symbol is given",
+    })
+
+    -- Watch the given expression, the visual selection, or the expression under the cursor
+    local function watch_command(kind)
+        return function(opts)
+            if opts.args ~= "" then
+                watchpoints.create(kind, opts.args)
+            elseif opts.range > 0 then
+                watchpoints.create_from_selection(kind)
+            else
+                watchpoints.create(kind)
+            end
+        end
+    end
+
+    vim.api.nvim_create_user_command("RustDebugWatch", watch_command("watch"), {
+        nargs = "?",
+        range = true,
+        desc = "Stop when an expression is written (watch)",
+    })
+
+    vim.api.nvim_create_user_command("RustDebugRWatch", watch_command("rwatch"), {
+        nargs = "?",
+        range = true,
+        desc = "Stop when an expression is read (rwatch)",
+    })
+
+    vim.api.nvim_create_user_command("RustDebugAWatch", watch_command("awatch"), {
+        nargs = "?",
+        range = true,
+        desc = "Stop when an expression is read or written (awatch)",
+    })
+
+    vim.api.nvim_create_user_command("RustDebugBreakToggleEnabled", breakpoints.toggle_enabled, {
+        desc = "Enable or disable the breakpoint at current line without deleting it",
+    })
+
+    vim.api.nvim_create_user_command("RustDebugBreakConfirm", function(opts)
+        if opts.bang then
+            local count = breakpoints.confirm_all()
+            vim.notify(string.format("Confirmed %d breakpoint(s)", count), vim.log.levels.INFO)
+        else
+            breakpoints.confirm_curline()
+        end
+    end, {
+        bang = true,
+        desc = "Accept where the breakpoint at current line was restored (! for all breakpoints)",
+    })
+
+    vim.api.nvim_create_user_command("RustDebugBreakGroup", function(opts)
+        if opts.args ~= "" then
+            local bufnr = vim.api.nvim_get_current_buf()
+            local line = vim.api.nvim_win_get_cursor(0)[1] - 1
+            if not breakpoints.set_group_at(bufnr, line, opts.args) then
+                vim.notify("No breakpoint on this line", vim.log.levels.INFO)
+            end
+        else
+            breakpoints.group_curline()
+        end
+    end, {
+        nargs = "?",
+        complete = function()
+            return breakpoints.get_groups()
+        end,
+        desc = "Put the breakpoint at current line in a named group",
+    })
+
+    local group_actions = {
+        enable = breakpoints.enable_group,
+        disable = breakpoints.disable_group,
+        delete = breakpoints.delete_group,
+        restore = breakpoints.restore_group,
+    }
+
+    vim.api.nvim_create_user_command("RustDebugGroup", function(opts)
+        local action = group_actions[opts.fargs[1]]
+        local name = opts.fargs[2]
+        if not action or not name then
+            vim.notify("Usage: RustDebugGroup enable|disable|delete|restore <group>", vim.log.levels.WARN)
+            return
+        end
+        action(name)
+    end, {
+        nargs = "+",
+        complete = function(_, cmdline)
+            local args = vim.split(cmdline, "%s+", { trimempty = false })
+            if #args <= 2 then
+                return { "enable", "disable", "delete", "restore" }
+            end
+            return breakpoints.get_groups()
+        end,
+        desc = "Enable, disable, delete (park) or restore a group of breakpoints",
+    })
+
     vim.api.nvim_create_user_command("RustDebugClear", breakpoints.delete_all, {
         desc = "Clear all breakpoints",
     })
@@ -30,7 +148,31 @@ commands.create = function()
         desc = "Rebuild code and reload binary in active debug session",
     })
 
-    vim.api.nvim_create_user_command("RustDebugPinThread", scheduler.lock, {
+    vim.api.nvim_create_user_command("RustDebugCatchPanic", panics.toggle, {
+        desc = "Toggle stopping on Rust panics in this workspace",
+    })
+
+    vim.api.nvim_create_user_command("RustDebugUp", function(opts)
+        callstack.up(opts.count)
+    end, {
+        count = 1,
+        desc = "Select the calling frame in GDB and show it",
+    })
+
+    vim.api.nvim_create_user_command("RustDebugDown", function(opts)
+        callstack.down(opts.count)
+    end, {
+        count = 1,
+        desc = "Select the called frame in GDB and show it",
+    })
+
+    vim.api.nvim_create_user_command("RustDebugPinThread", function(opts)
+        scheduler.lock(opts.args ~= "" and opts.args or nil)
+    end, {
+        nargs = "?",
+        complete = function()
+            return { "on", "step", "replay" }
+        end,
         desc = "Lock scheduler; debug current thread",
     })
 
@@ -59,6 +201,42 @@ commands.create = function()
             vim.notify("Telescope.nvim is not installed", vim.log.levels.WARN)
         end
     end, { desc = "Show breakpoints in Telescope picker" })
+
+    vim.api.nvim_create_user_command("RustDebugWatchpoints", function()
+        local has_telescope, telescope_integration = pcall(require, "telescope_integration")
+        if has_telescope then
+            telescope_integration.show_watchpoints()
+        else
+            vim.notify("Telescope.nvim is not installed", vim.log.levels.WARN)
+        end
+    end, { desc = "Show watchpoints in Telescope picker" })
+
+    vim.api.nvim_create_user_command("RustDebugOrphans", function()
+        local has_telescope, telescope_integration = pcall(require, "telescope_integration")
+        if has_telescope then
+            telescope_integration.show_orphans()
+        else
+            vim.notify("Telescope.nvim is not installed", vim.log.levels.WARN)
+        end
+    end, { desc = "Review breakpoints that couldn't be restored in Telescope picker" })
+
+    vim.api.nvim_create_user_command("RustDebugBacktrace", function()
+        local has_telescope, telescope_integration = pcall(require, "telescope_integration")
+        if has_telescope then
+            telescope_integration.show_backtrace()
+        else
+            vim.notify("Telescope.nvim is not installed", vim.log.levels.WARN)
+        end
+    end, { desc = "Show the call stack in Telescope picker" })
+
+    vim.api.nvim_create_user_command("RustDebugThreads", function()
+        local has_telescope, telescope_integration = pcall(require, "telescope_integration")
+        if has_telescope then
+            telescope_integration.show_threads()
+        else
+            vim.notify("Telescope.nvim is not installed", vim.log.levels.WARN)
+        end
+    end, { desc = "Switch threads in Telescope picker" })
 end
 
 return commands
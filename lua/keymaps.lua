@@ -1,8 +1,10 @@
 local breakpoints = require("breakpoints")
+local callstack = require("callstack")
 local scheduler = require("scheduler")
 local cargo = require("cargo")
 local process = require("process")
 local termdebug = require("termdebug")
+local watchpoints = require("watchpoints")
 
 local keymaps = {}
 
@@ -22,7 +24,51 @@ keymaps.default = function()
     vim.keymap.set("n", "<leader>du", cargo.clear_pins, { desc = "Clear pinned selections", noremap = true, silent = true })
     vim.keymap.set("n", "<leader>dv", "<cmd>Var<cr>", { desc = "Show vars pane" })
     vim.keymap.set("n", "<leader>dP", scheduler.unlock, { desc = "Unpin thread" })
+    vim.keymap.set("n", "<leader>dk", function()
+        callstack.up(vim.v.count1)
+    end, { desc = "Up the call stack", noremap = true, silent = true })
+    vim.keymap.set("n", "<leader>dj", function()
+        callstack.down(vim.v.count1)
+    end, { desc = "Down the call stack", noremap = true, silent = true })
     vim.keymap.set("n", "<leader>b", breakpoints.toggle, { desc = "Toggle breakpoint", remap = true, silent = true })
+    vim.keymap.set(
+        "n",
+        "<leader>dc",
+        breakpoints.create_conditional,
+        { desc = "Conditional breakpoint", noremap = true, silent = true }
+    )
+    vim.keymap.set(
+        "n",
+        "<leader>dm",
+        breakpoints.create_logpoint,
+        { desc = "Logpoint", noremap = true, silent = true }
+    )
+    vim.keymap.set(
+        "n",
+        "<leader>di",
+        breakpoints.create_hit_count,
+        { desc = "Hit count breakpoint", noremap = true, silent = true }
+    )
+    vim.keymap.set(
+        "n",
+        "<leader>df",
+        breakpoints.create_function,
+        { desc = "Function breakpoint", noremap = true, silent = true }
+    )
+    vim.keymap.set(
+        "n",
+        "<leader>dd",
+        breakpoints.toggle_enabled,
+        { desc = "Enable/disable breakpoint", noremap = true, silent = true }
+    )
+    vim.keymap.set(
+        "n",
+        "<leader>dg",
+        breakpoints.group_curline,
+        { desc = "Set breakpoint group", noremap = true, silent = true }
+    )
+    vim.keymap.set("n", "<leader>dw", watchpoints.create, { desc = "Watch expression", noremap = true, silent = true })
+    vim.keymap.set("x", "<leader>dw", ":RustDebugWatch<cr>", { desc = "Watch selection", noremap = true, silent = true })
     vim.keymap.set(
         "n",
         "<leader>db",
@@ -41,6 +87,38 @@ keymaps.telescope = function()
             vim.notify("Telescope.nvim is not installed", vim.log.levels.WARN)
         end
     end, { desc = "List breakpoints (Telescope)", noremap = true, silent = true })
+    vim.keymap.set("n", "<leader>dW", function()
+        local has_telescope, telescope_integration = pcall(require, "telescope_integration")
+        if has_telescope then
+            telescope_integration.show_watchpoints()
+        else
+            vim.notify("Telescope.nvim is not installed", vim.log.levels.WARN)
+        end
+    end, { desc = "List watchpoints (Telescope)", noremap = true, silent = true })
+    vim.keymap.set("n", "<leader>dO", function()
+        local has_telescope, telescope_integration = pcall(require, "telescope_integration")
+        if has_telescope then
+            telescope_integration.show_orphans()
+        else
+            vim.notify("Telescope.nvim is not installed", vim.log.levels.WARN)
+        end
+    end, { desc = "Review orphaned breakpoints (Telescope)", noremap = true, silent = true })
+    vim.keymap.set("n", "<leader>dB", function()
+        local has_telescope, telescope_integration = pcall(require, "telescope_integration")
+        if has_telescope then
+            telescope_integration.show_backtrace()
+        else
+            vim.notify("Telescope.nvim is not installed", vim.log.levels.WARN)
+        end
+    end, { desc = "Show backtrace (Telescope)", noremap = true, silent = true })
+    vim.keymap.set("n", "<leader>dT", function()
+        local has_telescope, telescope_integration = pcall(require, "telescope_integration")
+        if has_telescope then
+            telescope_integration.show_threads()
+        else
+            vim.notify("Telescope.nvim is not installed", vim.log.levels.WARN)
+        end
+    end, { desc = "Switch thread (Telescope)", noremap = true, silent = true })
 end
 
 return keymaps
@@ -0,0 +1,107 @@
+-- Context line locator strategy
+-- Stores the lines around the breakpoint, like a patch hunk, and finds the
+-- place where the most of them still match, the way `git apply` applies a hunk
+-- at an offset. Tells apart repeated lines like `}` or `Ok(())` by their surroundings
+
+local M = {}
+
+M.failure_reason = "context not found"
+
+-- Lines of context stored on each side
+local CONTEXT_LINES = 3
+
+-- Lines next to line_0indexed, nearest first; step is -1 for above, 1 for below
+local function neighbours(lines, line_0indexed, step)
+    local result = {}
+    for k = 1, CONTEXT_LINES do
+        local line = lines[line_0indexed + 1 + k * step]
+        if line == nil then
+            break
+        end
+        table.insert(result, vim.trim(line))
+    end
+    return result
+end
+
+-- Prepare data for persistence
+M.prepare = function(line_content, bufnr, line_0indexed)
+    local data = { line = vim.trim(line_content) }
+    if bufnr then
+        local lines = vim.api.nvim_buf_get_lines(bufnr, 0, -1, false)
+        data.before = neighbours(lines, line_0indexed, -1)
+        data.after = neighbours(lines, line_0indexed, 1)
+    end
+    return data
+end
+
+-- How many stored context lines match around index i (1-indexed) of lines
+local function context_matches(lines, trimmed, i, stored, step)
+    local count = 0
+    for k, expected in ipairs(stored or {}) do
+        local j = i + k * step
+        if lines[j] ~= nil and trimmed(j) == expected then
+            count = count + 1
+        end
+    end
+    return count
+end
+
+-- Find line in buffer by matching the line and its context
+-- Returns 0-indexed line number and confidence, or nil
+M.find = function(bufnr, stored_data, original_line_0indexed)
+    if not stored_data or not stored_data.line then
+        return nil
+    end
+
+    local lines = vim.api.nvim_buf_get_lines(bufnr, 0, -1, false)
+    local trimmed_lines = {}
+    local function trimmed(i)
+        trimmed_lines[i] = trimmed_lines[i] or vim.trim(lines[i])
+        return trimmed_lines[i]
+    end
+
+    local context_size = #(stored_data.before or {}) + #(stored_data.after or {})
+    -- An edited line is still found if enough of its context is intact
+    local min_context = math.max(1, math.ceil(context_size / 2))
+
+    local best_line = nil
+    local best_matches = 0
+    local best_score = -1
+    local best_distance = math.huge
+
+    for i = 1, #lines do
+        local matches = context_matches(lines, trimmed, i, stored_data.before, -1)
+            + context_matches(lines, trimmed, i, stored_data.after, 1)
+
+        local score = nil
+        if trimmed(i) == stored_data.line then
+            -- A matching line always beats an edited one
+            score = matches + context_size + 1
+        elseif matches >= min_context then
+            score = matches
+        end
+
+        if score then
+            local distance = math.abs(i - 1 - original_line_0indexed)
+            if score > best_score or (score == best_score and distance < best_distance) then
+                best_line = i - 1
+                best_matches = matches
+                best_score = score
+                best_distance = distance
+            end
+        end
+    end
+
+    if not best_line then
+        return nil
+    end
+
+    -- The line itself matching counts for most of the confidence
+    local context_share = context_size > 0 and best_matches / context_size or 0
+    if best_score > context_size then
+        return best_line, 0.7 + 0.3 * context_share
+    end
+    return best_line, 0.6 * context_share
+end
+
+return M
@@ -0,0 +1,31 @@
+-- Exact-if-unchanged line locator strategy
+-- Restores breakpoints at their saved line numbers, but only if the line still
+-- has the same content; meant to go first in a chain of strategies
+
+local M = {}
+
+M.failure_reason = "line changed"
+
+-- Prepare data for persistence
+M.prepare = function(line_content)
+    return {
+        line = vim.trim(line_content),
+    }
+end
+
+-- Find line in buffer
+-- Returns 0-indexed line number or nil
+M.find = function(bufnr, stored_data, original_line_0indexed)
+    if not stored_data or not stored_data.line then
+        return nil
+    end
+
+    local lines = vim.api.nvim_buf_get_lines(bufnr, original_line_0indexed, original_line_0indexed + 1, false)
+    if #lines == 0 or vim.trim(lines[1]) ~= stored_data.line then
+        return nil
+    end
+
+    return original_line_0indexed, 1
+end
+
+return M
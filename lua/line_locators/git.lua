@@ -0,0 +1,121 @@
+-- Git line locator strategy
+-- Records the commit checked out when the breakpoint was saved, and on restore
+-- maps the line through the diff between that commit's version of the file and
+-- the buffer, following moves from commits and branch switches exactly
+-- Lines git can't follow (files outside a repo, or lines that were edited) are
+-- found with the context strategy instead
+
+local context = require("line_locators.context")
+
+local M = {}
+
+M.failure_reason = context.failure_reason
+
+-- File contents by "commit:path"; a commit's version of a file never changes
+local file_cache = {}
+
+local function git(file, args)
+    local dir = vim.fn.fnamemodify(file, ":p:h")
+    local output = vim.fn.systemlist(vim.list_extend({ "git", "-C", dir }, args))
+    if vim.v.shell_error ~= 0 then
+        return nil
+    end
+    return output
+end
+
+-- Commit checked out in file's repository, or nil outside one
+-- Looked up once per repository in a save, however many breakpoints it has
+local function head_commit(file, save)
+    local git_path = vim.fs.find(".git", { upward = true, path = vim.fn.fnamemodify(file, ":p:h") })[1]
+    if not git_path then
+        return nil
+    end
+
+    save = save or {}
+    save.git_heads = save.git_heads or {}
+    if save.git_heads[git_path] == nil then
+        local output = git(file, { "rev-parse", "HEAD" })
+        save.git_heads[git_path] = output and output[1] or false
+    end
+    return save.git_heads[git_path] or nil
+end
+
+-- Lines of file as of commit, or nil if git doesn't know it
+local function committed_lines(file, commit)
+    local key = commit .. ":" .. file
+    if file_cache[key] == nil then
+        local output = git(file, { "show", commit .. ":./" .. vim.fn.fnamemodify(file, ":t") })
+        file_cache[key] = output or false
+    end
+    return file_cache[key] or nil
+end
+
+-- Map a 1-indexed line of old_lines to new_lines through their diff hunks
+-- Returns nil if the line itself was changed or removed
+local function map_line(old_lines, new_lines, line)
+    local hunks = vim.diff(table.concat(old_lines, "\n") .. "\n", table.concat(new_lines, "\n") .. "\n", {
+        result_type = "indices",
+    })
+
+    local offset = 0
+    for _, hunk in ipairs(hunks) do
+        local start_old, count_old, _, count_new = unpack(hunk)
+        if count_old == 0 then
+            -- Pure insertion after start_old
+            if line <= start_old then
+                break
+            end
+        elseif line < start_old then
+            break
+        elseif line < start_old + count_old then
+            return nil
+        end
+        offset = offset + count_new - count_old
+    end
+    return line + offset
+end
+
+-- Prepare data for persistence: the context strategy's data, plus the commit
+-- and the line's number in that commit's version of the file
+M.prepare = function(line_content, bufnr, line_0indexed, save)
+    local data = context.prepare(line_content, bufnr, line_0indexed)
+    local file = bufnr and vim.api.nvim_buf_get_name(bufnr)
+    if not file or file == "" then
+        return data
+    end
+
+    local commit = head_commit(file, save)
+    local old_lines = commit and committed_lines(file, commit)
+    if not old_lines then
+        return data
+    end
+
+    -- The buffer may have changes that aren't committed yet
+    local buffer_lines = vim.api.nvim_buf_get_lines(bufnr, 0, -1, false)
+    local committed_line = map_line(buffer_lines, old_lines, line_0indexed + 1)
+    if committed_line then
+        data.commit = commit
+        data.commit_line = committed_line
+    end
+    return data
+end
+
+-- Find line in buffer by following the diff since the saved commit, falling
+-- back to matching the line's context
+-- Returns 0-indexed line number and confidence, or nil
+M.find = function(bufnr, stored_data, original_line_0indexed)
+    if stored_data and stored_data.commit then
+        local old_lines = committed_lines(vim.api.nvim_buf_get_name(bufnr), stored_data.commit)
+        if old_lines then
+            local buffer_lines = vim.api.nvim_buf_get_lines(bufnr, 0, -1, false)
+            local line = map_line(old_lines, buffer_lines, stored_data.commit_line)
+            if line and line <= #buffer_lines then
+                return line - 1, 1
+            end
+        end
+    end
+
+    return context.find(bufnr, stored_data, original_line_0indexed)
+end
+
+return M
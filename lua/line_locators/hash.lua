@@ -20,7 +20,7 @@ M.prepare = function(line_content)
 end
 
 -- Find line in buffer by matching hash
--- Returns 0-indexed line number or nil
+-- Returns 0-indexed line number and confidence, or nil
 M.find = function(bufnr, stored_data, original_line_0indexed)
     if not stored_data or not stored_data.line_hash then
         return nil
@@ -38,7 +38,7 @@ M.find = function(bufnr, stored_data, original_line_0indexed)
             if line_hash == target_hash then
                 -- Exact position match - return immediately
                 if i == original_line_0indexed then
-                    return i
+                    return i, 1
                 end
                 table.insert(matching_lines, i)
             end
@@ -50,10 +50,10 @@ M.find = function(bufnr, stored_data, original_line_0indexed)
     end
 
     if #matching_lines == 1 then
-        return matching_lines[1]
+        return matching_lines[1], 0.9
     end
 
-    -- Multiple matches: find nearest to original line
+    -- Multiple matches: find nearest to original line, which may be the wrong one
     local best_line = matching_lines[1]
     local best_distance = math.abs(matching_lines[1] - original_line_0indexed)
     for _, line in ipairs(matching_lines) do
@@ -64,7 +64,7 @@ M.find = function(bufnr, stored_data, original_line_0indexed)
         end
     end
 
-    return best_line
+    return best_line, 0.6
 end
 
 return M
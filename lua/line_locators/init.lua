@@ -1,8 +1,11 @@
 -- Line locator strategies for breakpoint persistence
 -- Each strategy implements:
---   prepare(line_content) -> data to store in persistence file
---   find(bufnr, stored_data, original_line_0indexed) -> 0-indexed line or nil
+--   prepare(line_content, bufnr, line_0indexed, save) -> data to store in persistence file;
+--     save is a table shared by every prepare of one save, to look things up once per save
+--   find(bufnr, stored_data, original_line_0indexed) -> 0-indexed line or nil, and
+--     optionally how confident the strategy is in the match, from 0 to 1 (default 1)
 --   failure_reason -> string describing why a match failed
+--   configure(persist_breakpoints_config) -> optional, applies user settings
 
 local M = {}
 
@@ -14,11 +17,72 @@ M.register = function(name, strategy)
     strategies[name] = strategy
 end
 
--- Get a strategy by name
+-- Confidence at which a chain stops trying later strategies
+local min_confidence = 0.8
+
+-- Strategy that tries the named strategies in turn, keeping each one's data by name
+-- A match below min_confidence is only used if no later strategy does better
+-- Its find also returns the name of the strategy that found the line
+local function chain(names)
+    local chained = {}
+    for _, name in ipairs(names) do
+        if strategies[name] then
+            table.insert(chained, { name = name, strategy = strategies[name] })
+        end
+    end
+
+    return {
+        failure_reason = "no strategy found the line",
+
+        prepare = function(line_content, bufnr, line_0indexed, save)
+            local data = {}
+            for _, link in ipairs(chained) do
+                data[link.name] = link.strategy.prepare(line_content, bufnr, line_0indexed, save)
+            end
+            return data
+        end,
+
+        find = function(bufnr, stored_data, original_line_0indexed)
+            local best = nil
+            for _, link in ipairs(chained) do
+                local data = stored_data and stored_data[link.name]
+                local line, confidence = link.strategy.find(bufnr, data, original_line_0indexed)
+                if line then
+                    confidence = confidence or 1
+                    if confidence >= min_confidence then
+                        return line, confidence, link.name
+                    end
+                    if not best or confidence > best.confidence then
+                        best = { line = line, confidence = confidence, name = link.name }
+                    end
+                end
+            end
+            if best then
+                return best.line, best.confidence, best.name
+            end
+            return nil
+        end,
+    }
+end
+
+-- Get a strategy by name, or a chain of strategies from a list of names
 M.get = function(name)
+    if type(name) == "table" then
+        return chain(name)
+    end
     return strategies[name]
 end
 
+-- Pass the persist_breakpoints settings to the strategies that take any
+M.configure = function(config)
+    min_confidence = config and config.min_confidence or 0.8
+    for _, strategy in pairs(strategies) do
+        if strategy.configure then
+            strategy.configure(config)
+        end
+    end
+end
+
 -- List available strategy names
 M.available = function()
     local names = {}
@@ -32,8 +96,12 @@ end
 -- Load built-in strategies
 local function load_builtin_strategies()
     M.register("exact", require("line_locators.exact"))
+    M.register("exact_if_unchanged", require("line_locators.exact_if_unchanged"))
     M.register("hash", require("line_locators.hash"))
     M.register("jaccard", require("line_locators.jaccard"))
+    M.register("treesitter", require("line_locators.treesitter"))
+    M.register("context", require("line_locators.context"))
+    M.register("git", require("line_locators.git"))
 end
 
 load_builtin_strategies()
@@ -1,17 +1,32 @@
 -- Jaccard line locator strategy
--- Matches lines by token-based Jaccard similarity with position bonus
+-- Matches lines by token-based Jaccard similarity, weighed down by distance
+-- from the original line so short lines don't match look-alikes far away
 -- Handles minor edits like variable renames
 
 local M = {}
 
 M.failure_reason = "no similar line found"
 
--- Minimum similarity threshold to consider a match
-local THRESHOLD = 0.5
+-- Settings from persist_breakpoints.jaccard:
+-- threshold: minimum score to consider a match
+-- tokenizer: "words", "rust", or a function(line_content) returning a set of tokens
+-- distance_weight: how much distance lowers the score; a line n lines away
+--   scores similarity / (1 + distance_weight * n), so 0 only breaks ties
+local DEFAULTS = {
+    threshold = 0.5,
+    tokenizer = "rust",
+    distance_weight = 0.05,
+}
+
+local settings = DEFAULTS
+
+-- Apply persist_breakpoints settings
+M.configure = function(config)
+    settings = vim.tbl_extend("force", DEFAULTS, config and config.jaccard or {})
+end
 
 -- Tokenize a line into a set of alphanumeric tokens
--- This is the base tokenizer; can be overridden for language-specific behavior
-M.tokenize = function(line_content)
+M.tokenize_words = function(line_content)
     local tokens = {}
     for token in line_content:gmatch("[%w_]+") do
         tokens[token] = true
@@ -19,6 +34,61 @@ M.tokenize = function(line_content)
     return tokens
 end
 
+-- Tokenize a line of Rust, keeping paths (`std::io::Result`), lifetimes (`'a`)
+-- and macro names (`println!`) as single tokens, and comparison operators
+-- (`==`, `!=`, `<=`, `>=`) as tokens of their own
+M.tokenize_rust = function(line_content)
+    local tokens = {}
+    local pos = 1
+    while pos <= #line_content do
+        local s, e = line_content:find("^[%a_][%w_]*", pos)
+        if s then
+            -- Path segments
+            while true do
+                local _, segment_end = line_content:find("^::[%a_][%w_]*", e + 1)
+                if not segment_end then
+                    break
+                end
+                e = segment_end
+            end
+            -- Macro invocation, not a comparison like `a!=b`
+            if line_content:sub(e + 1, e + 1) == "!" and line_content:sub(e + 2, e + 2) ~= "=" then
+                e = e + 1
+            end
+        else
+            s, e = line_content:find("^'[%a_][%w_]*", pos)
+            -- A lifetime, not a char literal like 'a'
+            if s and line_content:sub(e + 1, e + 1) == "'" then
+                s = nil
+            end
+            if not s then
+                s, e = line_content:find("^%d[%w_]*", pos)
+            end
+            if not s then
+                s, e = line_content:find("^[=!<>]=", pos)
+            end
+        end
+
+        if s then
+            tokens[line_content:sub(s, e)] = true
+            pos = e + 1
+        else
+            pos = pos + 1
+        end
+    end
+    return tokens
+end
+
+-- Tokenize a line with the configured tokenizer
+M.tokenize = function(line_content)
+    if type(settings.tokenizer) == "function" then
+        return settings.tokenizer(line_content)
+    elseif settings.tokenizer == "rust" then
+        return M.tokenize_rust(line_content)
+    end
+    return M.tokenize_words(line_content)
+end
+
 -- Compute Jaccard similarity between two token sets
 -- Returns value between 0 and 1
 M.similarity = function(tokens1, tokens2)
@@ -54,8 +124,8 @@ M.prepare = function(line_content)
     }
 end
 
--- Find line in buffer by Jaccard similarity
--- Returns 0-indexed line number or nil
+-- Find line in buffer by Jaccard similarity and distance
+-- Returns 0-indexed line number and its score as the confidence, or nil
 M.find = function(bufnr, stored_data, original_line_0indexed)
     if not stored_data or not stored_data.line_content then
         return nil
@@ -82,22 +152,24 @@ M.find = function(bufnr, stored_data, original_line_0indexed)
         local lines = vim.api.nvim_buf_get_lines(bufnr, i, i + 1, false)
         if lines and #lines > 0 then
             local line_tokens = M.tokenize(lines[1])
-            local sim = M.similarity(target_tokens, line_tokens)
-
-            if sim >= THRESHOLD then
-                local distance = math.abs(i - original_line_0indexed)
+            local distance = math.abs(i - original_line_0indexed)
+            local score = M.similarity(target_tokens, line_tokens) / (1 + settings.distance_weight * distance)
 
-                -- Prefer higher similarity, then closer position
-                if sim > best_score or (sim == best_score and distance < best_distance) then
+            if score >= settings.threshold then
+                -- Prefer higher score, then closer position
+                if score > best_score or (score == best_score and distance < best_distance) then
                     best_line = i
-                    best_score = sim
+                    best_score = score
                     best_distance = distance
                 end
             end
         end
     end
 
-    return best_line
+    if not best_line then
+        return nil
+    end
+    return best_line, best_score
 end
 
 return M
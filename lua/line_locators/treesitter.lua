@@ -0,0 +1,249 @@
+-- Tree-sitter line locator strategy
+-- Anchors breakpoints to their enclosing function (eg `impl Display for Point > fn fmt`)
+-- and the index and text of the statement within its body, so they survive
+-- reformatting, reordering functions and edits elsewhere in the file
+-- Needs the rust tree-sitter parser; lines outside functions restore at their saved line
+
+local M = {}
+
+M.failure_reason = "enclosing function not found"
+
+-- Comments don't count as statements, so adding one doesn't shift the anchor
+local COMMENT_TYPES = { line_comment = true, block_comment = true }
+
+-- Parse a buffer as Rust; returns the root node, or nil without a parser
+local function parse(bufnr)
+    local ok, parser = pcall(vim.treesitter.get_parser, bufnr, "rust")
+    if not ok or not parser then
+        return nil
+    end
+    local trees = parser:parse()
+    return trees[1] and trees[1]:root()
+end
+
+local function field_text(node, field, bufnr)
+    local child = node:field(field)[1]
+    if not child then
+        return nil
+    end
+    -- rustfmt may wrap long types and generics; compare them on one line
+    return (vim.treesitter.get_node_text(child, bufnr):gsub("%s+", " "))
+end
+
+-- Name of a node that scopes functions, or nil for other nodes
+local function scope_name(node, bufnr)
+    local node_type = node:type()
+    if node_type == "function_item" then
+        return "fn " .. (field_text(node, "name", bufnr) or "")
+    elseif node_type == "impl_item" then
+        local trait = field_text(node, "trait", bufnr)
+        local type_name = field_text(node, "type", bufnr) or ""
+        return "impl " .. (trait and (trait .. " for ") or "") .. type_name
+    elseif node_type == "trait_item" then
+        return "trait " .. (field_text(node, "name", bufnr) or "")
+    elseif node_type == "mod_item" then
+        return "mod " .. (field_text(node, "name", bufnr) or "")
+    end
+end
+
+-- Scope names from the outermost item down to node, eg { "mod net", "fn connect" }
+local function scope_path(node, bufnr)
+    local path = {}
+    while node do
+        local name = scope_name(node, bufnr)
+        if name then
+            table.insert(path, 1, name)
+        end
+        node = node:parent()
+    end
+    return path
+end
+
+-- Statements in a function body, skipping comments
+local function statements(body)
+    local result = {}
+    for child in body:iter_children() do
+        if child:named() and not COMMENT_TYPES[child:type()] then
+            table.insert(result, child)
+        end
+    end
+    return result
+end
+
+-- Hash of code with whitespace and trailing commas dropped, so rustfmt
+-- wrapping a statement doesn't change it
+local function code_hash(text)
+    local normalized = text:gsub("%s+", ""):gsub(",([%)%]}])", "%1")
+    return vim.fn.sha256(normalized)
+end
+
+local function statement_hash(statement, bufnr)
+    return code_hash(vim.treesitter.get_node_text(statement, bufnr))
+end
+
+-- Innermost function containing node
+local function enclosing_function(node)
+    while node and node:type() ~= "function_item" do
+        node = node:parent()
+    end
+    return node
+end
+
+-- Prepare data for persistence
+M.prepare = function(line_content, bufnr, line_0indexed)
+    local root = bufnr and parse(bufnr)
+    local col = line_content:find("%S")
+    if not root or not col then
+        return nil
+    end
+
+    local node = root:named_descendant_for_range(line_0indexed, col - 1, line_0indexed, col - 1)
+    local fn = enclosing_function(node)
+    local body = fn and fn:field("body")[1]
+    if not body then
+        return nil
+    end
+
+    local data = { path = scope_path(fn, bufnr) }
+    local body_start, _, body_end = body:range()
+
+    if line_0indexed == body_end and line_0indexed ~= body_start then
+        data.closing = true
+        return data
+    end
+
+    -- The statement of the body the line belongs to, and how far into it the line is
+    for index, statement in ipairs(statements(body)) do
+        local start_row, _, end_row = statement:range()
+        if line_0indexed >= start_row and line_0indexed <= end_row then
+            data.statement = index
+            data.statement_hash = statement_hash(statement, bufnr)
+            data.offset = line_0indexed - start_row
+            data.line_hash = code_hash(line_content)
+            return data
+        end
+    end
+
+    -- The signature, or a blank line between statements
+    data.offset = line_0indexed - fn:start()
+    return data
+end
+
+-- Index of the statement matching hash, nearest to index first
+local function find_statement(stmts, index, hash, bufnr)
+    for distance = 0, #stmts do
+        for _, candidate in ipairs({ index - distance, index + distance }) do
+            if stmts[candidate] and statement_hash(stmts[candidate], bufnr) == hash then
+                return candidate
+            end
+        end
+    end
+    return nil
+end
+
+-- Line of statement the breakpoint was on: the line with the saved text nearest
+-- to the saved offset, or nil if the statement was rewrapped
+local function find_line_in(statement, bufnr, stored_data)
+    local start_row, _, end_row = statement:range()
+    local target = start_row + (stored_data.offset or 0)
+    local lines = vim.api.nvim_buf_get_lines(bufnr, start_row, end_row + 1, false)
+    local best = nil
+    for i, text in ipairs(lines) do
+        local row = start_row + i - 1
+        if code_hash(text) == stored_data.line_hash and (not best or math.abs(row - target) < math.abs(best - target)) then
+            best = row
+        end
+    end
+    return best
+end
+
+local function_query = nil
+
+-- Functions whose scope path matches path
+local function find_functions(root, bufnr, path)
+    function_query = function_query or vim.treesitter.query.parse("rust", "(function_item) @fn")
+    local matches = {}
+    for _, node in function_query:iter_captures(root, bufnr) do
+        if vim.deep_equal(scope_path(node, bufnr), path) then
+            table.insert(matches, node)
+        end
+    end
+    return matches
+end
+
+-- Find line in buffer by resolving the stored function and statement
+-- Returns 0-indexed line number and confidence, or nil
+M.find = function(bufnr, stored_data, original_line_0indexed)
+    if not stored_data or not stored_data.path then
+        -- Prepared outside a function or without a parser: use the saved line
+        if original_line_0indexed >= vim.api.nvim_buf_line_count(bufnr) then
+            return nil
+        end
+        return original_line_0indexed
+    end
+
+    local root = parse(bufnr)
+    if not root then
+        return nil
+    end
+
+    -- Functions can share a path, eg behind different #[cfg]s; take the nearest
+    local fn = nil
+    local best_distance = math.huge
+    for _, candidate in ipairs(find_functions(root, bufnr, stored_data.path)) do
+        local distance = math.abs(candidate:start() - original_line_0indexed)
+        if distance < best_distance then
+            fn = candidate
+            best_distance = distance
+        end
+    end
+    local body = fn and fn:field("body")[1]
+    if not body then
+        return nil
+    end
+
+    local _, _, body_end = body:range()
+    if stored_data.closing then
+        return body_end, 1
+    end
+
+    local offset = stored_data.offset or 0
+    if stored_data.statement then
+        local stmts = statements(body)
+        local index = stored_data.statement
+        local confidence = 1
+        if stored_data.statement_hash then
+            local found = find_statement(stmts, index, stored_data.statement_hash, bufnr)
+            if found then
+                -- Statements were added or removed above it
+                confidence = found == index and 1 or 0.9
+                index = found
+            else
+                -- The statement was edited; let a later strategy in a chain try
+                confidence = 0.3
+            end
+        end
+
+        local statement = stmts[index]
+        if not statement then
+            return nil
+        end
+        local start_row, _, end_row = statement:range()
+        if stored_data.line_hash and confidence > 0.3 then
+            local line = find_line_in(statement, bufnr, stored_data)
+            if line then
+                return line, confidence
+            elseif offset > 0 then
+                -- Rewrapped: GDB stops at the start of the statement anyway
+                return start_row, 0.7
+            end
+            return start_row, confidence
+        end
+        return math.min(start_row + offset, end_row), confidence
+    end
+
+    -- Lines between statements are only placed by their distance from the signature
+    return math.min(fn:start() + offset, body_end), 0.8
+end
+
+return M
@@ -0,0 +1,259 @@
+-- GDB/MI channel for reading structured state back from GDB
+-- termdebug keeps its own MI connection private, so we open a second MI
+-- interpreter on a pty we own with `new-ui mi <pty>`. GDB broadcasts async
+-- records (*stopped, =breakpoint-created, ...) to every UI, and result
+-- records for our own commands come back tagged with the token we sent.
+
+local mi = {}
+
+-- Job holding the pty that GDB's secondary MI interpreter talks to
+local job_id = nil
+
+-- Incomplete trailing output line from the last on_stdout chunk
+local partial_line = ""
+
+-- Token for the next command and callbacks waiting on result records
+local next_token = 1
+local callbacks = {}
+
+-- Async record handlers by class: { [class] = { handler, ... } }
+local listeners = {}
+
+-- Record type by prefix character
+local RECORD_TYPES = {
+    ["^"] = "result",
+    ["*"] = "exec",
+    ["+"] = "status",
+    ["="] = "notify",
+    ["~"] = "console",
+    ["@"] = "target",
+    ["&"] = "log",
+}
+
+local C_ESCAPES = {
+    n = "\n",
+    t = "\t",
+    r = "\r",
+    ['"'] = '"',
+    ["\\"] = "\\",
+}
+
+local parse_value
+
+-- Parse a C string starting at the opening quote; returns string, next index
+local function parse_cstring(s, i)
+    local out = {}
+    i = i + 1
+    while i <= #s do
+        local c = s:sub(i, i)
+        if c == "\\" then
+            local octal = s:match("^[0-7][0-7]?[0-7]?", i + 1)
+            if octal then
+                table.insert(out, string.char(tonumber(octal, 8)))
+                i = i + 1 + #octal
+            else
+                local escaped = s:sub(i + 1, i + 1)
+                table.insert(out, C_ESCAPES[escaped] or escaped)
+                i = i + 2
+            end
+        elseif c == '"' then
+            return table.concat(out), i + 1
+        else
+            table.insert(out, c)
+            i = i + 1
+        end
+    end
+    return table.concat(out), i
+end
+
+-- Parse comma-separated `name=value` results until the closing character
+-- Values without a name (emitted by some GDB versions) go in the array part
+local function parse_results(s, i, closing)
+    local results = {}
+    while i <= #s and s:sub(i, i) ~= closing do
+        local name = s:match("^[%w_%-]+", i)
+        local value
+        if name and s:sub(i + #name, i + #name) == "=" then
+            value, i = parse_value(s, i + #name + 1)
+            results[name] = value
+        else
+            value, i = parse_value(s, i)
+            table.insert(results, value)
+        end
+        if s:sub(i, i) == "," then
+            i = i + 1
+        end
+    end
+    return results, i + 1
+end
+
+-- Parse a list; lists of results (`[frame={...},frame={...}]`) become arrays of their values
+local function parse_list(s, i)
+    local list = {}
+    i = i + 1
+    while i <= #s and s:sub(i, i) ~= "]" do
+        local name = s:match("^[%w_%-]+=", i)
+        if name then
+            i = i + #name
+        end
+        local value
+        value, i = parse_value(s, i)
+        table.insert(list, value)
+        if s:sub(i, i) == "," then
+            i = i + 1
+        end
+    end
+    return list, i + 1
+end
+
+parse_value = function(s, i)
+    local c = s:sub(i, i)
+    if c == '"' then
+        return parse_cstring(s, i)
+    elseif c == "{" then
+        return parse_results(s, i + 1, "}")
+    elseif c == "[" then
+        return parse_list(s, i)
+    end
+    -- Not a valid value; skip to the next separator so parsing can continue
+    local stop = s:find("[,}%]]", i) or (#s + 1)
+    return s:sub(i, stop - 1), stop
+end
+
+-- Parse one line of MI output
+-- Returns { token = number?, type = string, class = string?, results = table?, text = string? }
+-- or nil for prompts, echoed input and anything else that isn't a record
+mi.parse = function(line)
+    local token, prefix, rest = line:match("^(%d*)([%^%*%+=~@&])(.*)$")
+    if not prefix then
+        return nil
+    end
+
+    local record = {
+        token = tonumber(token),
+        type = RECORD_TYPES[prefix],
+    }
+
+    if prefix == "~" or prefix == "@" or prefix == "&" then
+        record.text = parse_cstring(rest, 1)
+        return record
+    end
+
+    local class = rest:match("^[%w_%-]+")
+    if not class then
+        return nil
+    end
+    record.class = class
+
+    local results_str = rest:sub(#class + 1)
+    if results_str:sub(1, 1) == "," then
+        results_str = results_str:sub(2)
+    end
+    record.results = parse_results(results_str, 1, nil)
+
+    return record
+end
+
+-- Route a parsed record to the waiting callback or the async listeners
+local function dispatch(record)
+    if record.type == "result" then
+        local callback = record.token and callbacks[record.token]
+        if callback then
+            callbacks[record.token] = nil
+            callback(record)
+        end
+    elseif record.class and listeners[record.class] then
+        for _, handler in ipairs(listeners[record.class]) do
+            handler(record)
+        end
+    end
+end
+
+local function on_stdout(_, data)
+    data[1] = partial_line .. data[1]
+    partial_line = table.remove(data)
+
+    for _, line in ipairs(data) do
+        local record = mi.parse((line:gsub("\r$", "")))
+        if record then
+            dispatch(record)
+        end
+    end
+end
+
+-- Whether the MI channel is connected to a running GDB
+mi.is_active = function()
+    return job_id ~= nil
+end
+
+-- Open the secondary MI interpreter in the running termdebug GDB
+mi.start = function()
+    -- A previous session's channel is stale once a new GDB starts
+    mi.stop()
+
+    local id = vim.fn.jobstart({ "tail", "-f", "/dev/null" }, {
+        pty = true,
+        on_stdout = on_stdout,
+    })
+    if id <= 0 then
+        vim.notify("Could not open GDB/MI channel", vim.log.levels.WARN)
+        return false
+    end
+
+    local pty = vim.api.nvim_get_chan_info(id).pty
+    if not pty then
+        vim.fn.jobstop(id)
+        return false
+    end
+
+    job_id = id
+    vim.fn.TermDebugSendCommand("new-ui mi " .. pty)
+
+    -- Close the channel when termdebug shuts down
+    vim.api.nvim_create_autocmd("User", {
+        group = vim.api.nvim_create_augroup("RustTermdebugMi", { clear = true }),
+        pattern = "TermdebugStopPost",
+        once = true,
+        callback = function()
+            mi.stop()
+        end,
+        desc = "Close rust-termdebug GDB/MI channel",
+    })
+
+    return true
+end
+
+-- Close the MI channel
+mi.stop = function()
+    if job_id then
+        pcall(vim.fn.jobstop, job_id)
+    end
+    job_id = nil
+    partial_line = ""
+    callbacks = {}
+end
+
+-- Send an MI command (eg "-break-list"); callback receives the result record
+-- Returns false if the channel isn't active
+mi.send = function(command, callback)
+    if not job_id then
+        return false
+    end
+
+    local token = next_token
+    next_token = next_token + 1
+    if callback then
+        callbacks[token] = callback
+    end
+
+    vim.fn.chansend(job_id, token .. command .. "\r")
+    return true
+end
+
+-- Register a handler for async records of a class (eg "stopped", "breakpoint-created")
+mi.on = function(class, handler)
+    listeners[class] = listeners[class] or {}
+    table.insert(listeners[class], handler)
+end
+
+return mi
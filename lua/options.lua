@@ -27,7 +27,41 @@ local options = {
             -- Strategy for locating lines when restoring breakpoints
             -- 'exact': use saved line number directly (skips if out of range)
             -- 'hash': hash trimmed line content, match by content on restore
+            -- 'jaccard': match the most similar line by shared tokens
+            -- 'treesitter': match the enclosing function and statement (needs the rust parser)
+            -- 'context': match the line together with the lines around it, like a patch hunk
+            -- 'git': follow the diff since the commit the breakpoint was saved at,
+            --        falling back to 'context' outside git or for edited lines
+            -- Can also be a list tried in order, eg { "exact_if_unchanged", "hash", "jaccard" }
+            -- ('exact_if_unchanged' keeps the saved line only if its content is the same)
             line_locator = "exact",
+            -- Tuning for the 'jaccard' strategy
+            jaccard = {
+                -- Minimum score for a line to match
+                threshold = 0.5,
+                -- 'rust' splits into words, keeping paths, lifetimes and macro names
+                -- whole; 'words' splits on anything but letters, digits and _; or a
+                -- function(line) returning a set of tokens ({ [token] = true })
+                tokenizer = "rust",
+                -- Lower the score of lines further from the saved line: a line n lines
+                -- away scores similarity / (1 + distance_weight * n). 0 only breaks ties
+                distance_weight = 0.05,
+            },
+            -- Breakpoints restored with less confidence than this get a "●?" sign
+            -- until confirmed with :RustDebugBreakConfirm
+            min_confidence = 0.8,
+            -- Keep a separate breakpoint set for each git branch (or detached HEAD)
+            -- in .rust-termdebug.nvim/branches/, switching sets when the branch changes
+            per_branch = false,
+        },
+        -- Stop on Rust panics by breaking on rust_panic, core::panicking::panic_fmt
+        -- and std::panicking::begin_panic_handler when a debug session starts.
+        -- Can be `true`, `false`, or a table with options.  :RustDebugCatchPanic
+        -- toggles this per workspace, overriding `enabled`.
+        catch_panics = {
+            enabled = false,
+            -- Extra symbols to break on, eg error constructors like "anyhow::Error::msg"
+            extra_symbols = {},
         },
         -- Enable Telescope integration for listing breakpoints
         -- Requires telescope.nvim to be installed
@@ -63,6 +97,11 @@ options.init = function(options_in)
         options_in.persist_breakpoints = { enabled = false }
     end
 
+    -- Normalize catch_panics the same way
+    if type(options_in.catch_panics) == "boolean" then
+        options_in.catch_panics = { enabled = options_in.catch_panics }
+    end
+
     options.current = vim.tbl_deep_extend("force", options.defaults, options_in)
 end
 
@@ -0,0 +1,80 @@
+local options = require("options")
+local workspace = require("workspace")
+local mi = require("mi")
+
+local panics = {}
+
+-- Functions every Rust panic passes through
+local PANIC_SYMBOLS = {
+    "rust_panic",
+    "core::panicking::panic_fmt",
+    "std::panicking::begin_panic_handler",
+}
+
+-- Panic symbols plus any extra symbols (eg error constructors) from the options
+local function symbols()
+    local all = vim.list_extend({}, PANIC_SYMBOLS)
+    return vim.list_extend(all, options.current.catch_panics.extra_symbols or {})
+end
+
+-- Whether a breakpoint location is one of the panic symbols we set
+panics.is_panic_symbol = function(location)
+    return location ~= nil and vim.tbl_contains(symbols(), location)
+end
+
+-- Whether to stop on panics in this workspace
+-- The per-workspace setting from :RustDebugCatchPanic wins over the configured default
+panics.is_enabled = function()
+    local saved = workspace.get_setting("catch_panics")
+    if saved ~= nil then
+        return saved
+    end
+    return options.current.catch_panics.enabled
+end
+
+-- Set the panic breakpoints in the running GDB
+panics.arm = function()
+    for _, symbol in ipairs(symbols()) do
+        -- -f creates a pending breakpoint instead of prompting if the symbol isn't loaded yet
+        if not mi.send("-break-insert -f " .. symbol) then
+            pcall(vim.fn.TermDebugSendCommand, "break " .. symbol)
+        end
+    end
+end
+
+-- Arm the panic breakpoints if enabled for this workspace, eg when a debug
+-- session starts or the binary is reloaded
+panics.restore = function()
+    if panics.is_enabled() then
+        panics.arm()
+    end
+end
+
+-- Remove the panic breakpoints from the running GDB
+panics.disarm = function()
+    for _, symbol in ipairs(symbols()) do
+        pcall(vim.fn.TermDebugSendCommand, "clear " .. symbol)
+    end
+end
+
+-- Toggle stopping on panics for this workspace, applying it to the running session
+panics.toggle = function()
+    local enabled = not panics.is_enabled()
+    workspace.set_setting("catch_panics", enabled)
+
+    if mi.is_active() then
+        if enabled then
+            panics.arm()
+        else
+            panics.disarm()
+        end
+    end
+
+    if enabled then
+        vim.notify("Catching panics in this workspace", vim.log.levels.INFO)
+    else
+        vim.notify("No longer catching panics in this workspace", vim.log.levels.INFO)
+    end
+end
+
+return panics
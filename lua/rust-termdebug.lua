@@ -44,12 +44,21 @@ rust_termdebug.setup = function(options_in)
         -- Load breakpoints from previous session
         vim.defer_fn(function()
             breakpoints.load_from_disk()
+
+            -- Show breakpoints saved by other editors on this workspace; only
+            -- after loading, so a save during startup isn't applied twice
+            breakpoints.watch_file()
         end, 100) -- Delay to ensure buffers are loaded
 
+        -- Switch breakpoint sets when the git branch changes
+        if persist_config.per_branch then
+            breakpoints.watch_branch()
+        end
+
         -- Save breakpoints on exit
         vim.api.nvim_create_autocmd("VimLeavePre", {
             callback = function()
-                breakpoints.save_to_disk()
+                breakpoints.save_to_disk({ sync = true })
             end,
             desc = "Save rust-termdebug breakpoints before exit",
         })
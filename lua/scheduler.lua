@@ -1,14 +1,116 @@
+local mi = require("mi")
+
 local scheduler = {}
 
-scheduler.lock = function()
-    vim.fn.TermDebugSendCommand("set scheduler-locking on")
-    vim.notify("Scheduler pinned to current thread", vim.log.levels.INFO)
+-- GDB scheduler-locking modes
+-- off: every thread runs when stepping or continuing
+-- on: only the current thread runs
+-- step: only the current thread runs while stepping, all of them on continue
+-- replay: like on when replaying a recording, off otherwise
+scheduler.MODES = { "off", "on", "step", "replay" }
+
+local MESSAGES = {
+    off = "Scheduler unpinned",
+    on = "Scheduler pinned to current thread",
+    step = "Scheduler pinned to current thread while stepping",
+    replay = "Scheduler pinned to current thread while replaying",
+}
+
+-- Mode set in this debug session, or nil if it's still GDB's default
+local mode = nil
+
+-- Whether GDB has yet to accept the mode; it refuses locking until the
+-- program is running, so the mode is applied again on the next stop
+local pending = false
+
+-- Mode lock() uses when none is given: the last one pinned with
+local lock_mode = "on"
+
+-- Send the session's mode to GDB
+local function apply()
+    if not mode then
+        return
+    end
+
+    pending = true
+    local sent = mi.send("-gdb-set scheduler-locking " .. mode, function(record)
+        pending = record.class ~= "done"
+        vim.cmd("redrawstatus")
+    end)
+    if not sent then
+        pending = false
+        pcall(vim.fn.TermDebugSendCommand, "set scheduler-locking " .. mode)
+    end
+end
+
+-- Set the scheduler-locking mode for this debug session
+-- Returns false if the mode isn't one of scheduler.MODES
+scheduler.set_mode = function(new_mode)
+    if not vim.tbl_contains(scheduler.MODES, new_mode) then
+        vim.notify(
+            "Unknown scheduler-locking mode: " .. tostring(new_mode) .. " (off, on, step or replay)",
+            vim.log.levels.ERROR
+        )
+        return false
+    end
+
+    mode = new_mode
+    if new_mode ~= "off" then
+        lock_mode = new_mode
+    end
+    apply()
+    vim.cmd("redrawstatus")
+    vim.notify(MESSAGES[new_mode], vim.log.levels.INFO)
+    return true
+end
+
+-- The mode set in this debug session, or nil if it hasn't been changed
+scheduler.get_mode = function()
+    return mode
+end
+
+-- Pin the scheduler to the current thread
+-- mode is "on", "step" or "replay"; defaults to the last mode pinned with
+scheduler.lock = function(new_mode)
+    scheduler.set_mode(new_mode or lock_mode)
 end
 
 scheduler.unlock = function()
     -- Unlock the scheduler to allow debugging all threads again
-    vim.fn.TermDebugSendCommand("set scheduler-locking off")
-    vim.notify("Scheduler unpinned", vim.log.levels.INFO)
+    scheduler.set_mode("off")
+end
+
+-- Apply the session's mode again, eg after reloading the binary
+scheduler.restore = function()
+    apply()
+end
+
+-- Statusline component showing the mode while the scheduler is pinned, eg
+-- "sched:step", with "?" until GDB has accepted it
+scheduler.statusline = function()
+    if not mode or mode == "off" then
+        return ""
+    end
+    return "sched:" .. mode .. (pending and "?" or "")
 end
 
+-- The program is running now, so GDB accepts the mode
+mi.on("stopped", function()
+    if pending then
+        apply()
+    end
+end)
+
+-- A new debug session starts with GDB's default mode
+vim.api.nvim_create_autocmd("User", {
+    group = vim.api.nvim_create_augroup("RustTermdebugScheduler", { clear = true }),
+    pattern = "TermdebugStopPost",
+    callback = function()
+        mode = nil
+        pending = false
+        vim.cmd("redrawstatus")
+    end,
+    desc = "Forget rust-termdebug scheduler-locking mode",
+})
+
 return scheduler
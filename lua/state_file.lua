@@ -0,0 +1,170 @@
+-- Atomic, debounced writes for the files in .rust-termdebug.nvim
+-- Content is written to a temp file next to the target and renamed over it, so
+-- a crash mid-write never leaves a truncated file. Writes are debounced and run
+-- on libuv's thread pool so saving never blocks the editor.
+-- Content can be a function, called just before writing; returning nil skips the write.
+
+local uv = vim.uv or vim.loop
+
+local state_file = {}
+
+-- How long to wait for more changes before writing
+local DEBOUNCE_MS = 200
+
+-- Content waiting to be written: { [path] = content }
+local pending = {}
+
+-- Paths with an async write in progress
+local in_flight = {}
+
+-- Sequence number of each write, and of the newest write committed per path,
+-- so a slow async write never renames older content over a newer one
+local next_seq = 1
+local committed = {}
+
+local timer = nil
+
+local function claim_seq()
+    local seq = next_seq
+    next_seq = next_seq + 1
+    return seq
+end
+
+-- Rename the temp file into place unless a newer write already landed
+local function commit(tmp, path, seq)
+    if (committed[path] or 0) > seq then
+        uv.fs_unlink(tmp)
+        return true
+    end
+    local ok, err = uv.fs_rename(tmp, path)
+    if not ok then
+        uv.fs_unlink(tmp)
+        return false, err
+    end
+    committed[path] = seq
+    return true
+end
+
+local function report(path, err)
+    vim.schedule(function()
+        vim.notify(string.format("Could not write %s: %s", path, err), vim.log.levels.WARN)
+    end)
+end
+
+local write_pending
+
+-- Write content to path on the thread pool; the rename happens back on the main loop
+local function write_async(path, content)
+    local seq = claim_seq()
+    local tmp = string.format("%s.tmp.%d", path, seq)
+    in_flight[path] = true
+
+    local function done(err)
+        in_flight[path] = nil
+        if err then
+            uv.fs_unlink(tmp)
+            report(path, err)
+        else
+            local ok, rename_err = commit(tmp, path, seq)
+            if not ok then
+                report(path, rename_err)
+            end
+        end
+        -- Content that changed while this write was running
+        if pending[path] then
+            vim.schedule(write_pending)
+        end
+    end
+
+    uv.fs_open(tmp, "w", 420, function(open_err, fd)
+        if open_err then
+            return done(open_err)
+        end
+        uv.fs_write(fd, content, 0, function(write_err)
+            uv.fs_fsync(fd, function()
+                uv.fs_close(fd, function()
+                    done(write_err)
+                end)
+            end)
+        end)
+    end)
+end
+
+-- Start async writes for pending paths that aren't already being written
+write_pending = function()
+    for path, content in pairs(pending) do
+        if not in_flight[path] then
+            pending[path] = nil
+            if type(content) == "function" then
+                content = content()
+            end
+            if content then
+                write_async(path, content)
+            end
+        end
+    end
+end
+
+-- Write content to path atomically, right away
+state_file.write_sync = function(path, content)
+    pending[path] = nil
+    if type(content) == "function" then
+        content = content()
+    end
+    if not content then
+        return false
+    end
+
+    local seq = claim_seq()
+    local tmp = string.format("%s.tmp.%d", path, seq)
+    local file = io.open(tmp, "w")
+    if not file then
+        return false
+    end
+    file:write(content)
+    file:close()
+
+    return commit(tmp, path, seq)
+end
+
+-- Write content to path atomically after a short delay; later writes to the
+-- same path replace earlier ones that haven't been written yet
+state_file.write = function(path, content)
+    pending[path] = content
+
+    if not timer then
+        timer = uv.new_timer()
+    end
+    timer:stop()
+    timer:start(DEBOUNCE_MS, 0, vim.schedule_wrap(write_pending))
+end
+
+-- Write everything that's pending right away, eg before exiting
+state_file.flush = function()
+    for path, content in pairs(vim.tbl_extend("force", {}, pending)) do
+        state_file.write_sync(path, content)
+    end
+end
+
+-- Read a file, including a pending write that hasn't reached the disk yet
+-- Returns nil if the file doesn't exist
+state_file.read = function(path)
+    if pending[path] then
+        state_file.write_sync(path, pending[path])
+    end
+
+    local file = io.open(path, "r")
+    if not file then
+        return nil
+    end
+    local content = file:read("*a")
+    file:close()
+    return content
+end
+
+-- Whether a file exists or has a write pending
+state_file.exists = function(path)
+    return pending[path] ~= nil or in_flight[path] ~= nil or vim.fn.filereadable(path) == 1
+end
+
+return state_file
@@ -11,58 +11,106 @@ local action_state = require("telescope.actions.state")
 local previewers = require("telescope.previewers")
 
 local breakpoints = require("breakpoints")
+local watchpoints = require("watchpoints")
+local callstack = require("callstack")
+local threads = require("threads")
 
 local M = {}
 
--- Define a sign for breakpoint preview (matches the breakpoint extmark style)
-vim.fn.sign_define("RustDebugBreakpoint", {
-    text = "●",
-    texthl = "DiagnosticError",
-})
-
--- Show all breakpoints in a Telescope picker
-M.show_breakpoints = function(opts)
-    opts = opts or {}
-
-    -- Get all breakpoints
+-- Line breakpoints followed by function breakpoints
+local function all_breakpoints()
     local bp_list = breakpoints.get_all()
-
-    if #bp_list == 0 then
-        vim.notify("No breakpoints set", vim.log.levels.INFO)
-        return
+    for _, fb in ipairs(breakpoints.get_functions()) do
+        table.insert(bp_list, fb)
     end
+    return bp_list
+end
+
+-- Short "dir/file:line" form of a location
+local function short_location(file, line)
+    -- Get just the filename from the full path
+    local filename = vim.fn.fnamemodify(file, ":t")
+    local dir = vim.fn.fnamemodify(file, ":h:t")
+    return string.format("%s/%s:%d", dir, filename, line)
+end
 
-    -- Create display entries
+-- Build picker entries from breakpoints.get_all() and get_functions() results
+local function make_results(bp_list)
     local results = {}
     for i, bp in ipairs(bp_list) do
-        -- Get just the filename from the full path
-        local filename = vim.fn.fnamemodify(bp.file, ":t")
-        local dir = vim.fn.fnamemodify(bp.file, ":h:t")
+        local display
+        if bp.symbol then
+            display = "fn " .. bp.symbol
+            if bp.file and bp.line then
+                display = display .. "  (" .. short_location(bp.file, bp.line) .. ")"
+            end
+        else
+            display = short_location(bp.file, bp.line)
+        end
+        if bp.kind == "logpoint" then
+            display = display .. "  [log] " .. bp.message
+        end
+        if bp.condition then
+            display = display .. "  if " .. bp.condition
+        end
+        if bp.enabled == false then
+            display = display .. "  [disabled]"
+        end
+        if bp.unresolved then
+            display = display .. "  [unresolved]"
+        end
+        if bp.group then
+            display = display .. "  #" .. bp.group
+        end
+        if bp.unconfirmed then
+            display = display
+                .. string.format("  [%s %d%%, unconfirmed]", bp.placed_by, math.floor(bp.confidence * 100 + 0.5))
+        end
 
         table.insert(results, {
             index = i,
             file = bp.file,
             line = bp.line,
-            display = string.format("%s/%s:%d", dir, filename, bp.line),
-            ordinal = bp.file .. ":" .. bp.line,
+            bp = bp,
+            display = display,
+            ordinal = display .. " " .. (bp.file or ""),
         })
     end
+    return results
+end
+
+-- Create a finder over the given breakpoint list
+local function make_finder(bp_list)
+    return finders.new_table({
+        results = make_results(bp_list),
+        entry_maker = function(entry)
+            return {
+                value = entry,
+                display = entry.display,
+                ordinal = entry.ordinal,
+                filename = entry.file,
+                lnum = entry.line,
+            }
+        end,
+    })
+end
+
+-- Show all breakpoints in a Telescope picker
+M.show_breakpoints = function(opts)
+    opts = opts or {}
+
+    -- Get all breakpoints
+    local bp_list = all_breakpoints()
+
+    if #bp_list == 0 then
+        vim.notify("No breakpoints set", vim.log.levels.INFO)
+        return
+    end
 
     pickers
         .new(opts, {
             prompt_title = "Breakpoints",
-            finder = finders.new_table({
-                results = results,
-                entry_maker = function(entry)
-                    return {
-                        value = entry,
-                        display = entry.display,
-                        ordinal = entry.ordinal,
-                        filename = entry.file,
-                        lnum = entry.line,
-                    }
-                end,
-            }),
+            finder = make_finder(bp_list),
             sorter = conf.generic_sorter(opts),
             previewer = previewers.new_buffer_previewer({
                 title = "Breakpoint Preview",
@@ -70,6 +118,14 @@ M.show_breakpoints = function(opts)
                     return entry.filename
                 end,
                 define_preview = function(self, entry, status)
+                    -- Function breakpoints have no location until GDB resolves them
+                    if not entry.filename then
+                        vim.api.nvim_buf_set_lines(self.state.bufnr, 0, -1, false, {
+                            "Function breakpoint not resolved by GDB yet",
+                        })
+                        return
+                    end
+
                     conf.buffer_previewer_maker(entry.filename, self.state.bufnr, {
                         bufname = self.state.bufname,
                         winid = self.state.winid,
@@ -78,7 +134,6 @@ M.show_breakpoints = function(opts)
                             local ns = vim.api.nvim_create_namespace("telescope_bp_preview_hl")
 
                             -- Clear any existing preview breakpoint signs and highlights
-                            pcall(vim.fn.sign_unplace, "telescope_bp_preview", { buffer = bufnr })
                             pcall(vim.api.nvim_buf_clear_namespace, bufnr, ns, 0, -1)
 
                             -- Position cursor on the breakpoint line
@@ -100,9 +155,14 @@ M.show_breakpoints = function(opts)
                             )
 
                             -- Add breakpoint sign to show the icon (only for the selected breakpoint)
-                            pcall(vim.fn.sign_place, 0, "telescope_bp_preview", "RustDebugBreakpoint", bufnr, {
-                                lnum = entry.lnum,
-                            })
+                            pcall(
+                                vim.api.nvim_buf_set_extmark,
+                                bufnr,
+                                ns,
+                                entry.lnum - 1,
+                                0,
+                                breakpoints.sign_opts(entry.value.bp)
+                            )
                         end,
                     })
                 end,
@@ -112,57 +172,93 @@ M.show_breakpoints = function(opts)
                 actions.select_default:replace(function()
                     local selection = action_state.get_selected_entry()
                     actions.close(prompt_bufnr)
+                    if not selection.filename then
+                        vim.notify("No known location for " .. selection.display, vim.log.levels.INFO)
+                        return
+                    end
                     vim.cmd("edit " .. vim.fn.fnameescape(selection.filename))
                     vim.api.nvim_win_set_cursor(0, { selection.lnum, 0 })
                     vim.cmd("normal! zz")
                 end)
 
-                -- ctrl-d: delete breakpoint
-                map("i", "<C-d>", function()
+                -- ctrl-e: enable/disable breakpoint
+                map("i", "<C-e>", function()
                     local selection = action_state.get_selected_entry()
-                    if selection then
-                        local bufnr = vim.fn.bufnr(selection.filename)
+                    if not selection then
+                        return
+                    end
 
-                        -- Load buffer if not already loaded
+                    local bp = selection.value.bp
+                    local enabled = bp.enabled == false
+                    if bp.symbol then
+                        breakpoints.set_function_enabled(bp.symbol, enabled)
+                    else
+                        local bufnr = vim.fn.bufnr(selection.filename)
                         if bufnr == -1 then
                             bufnr = vim.fn.bufadd(selection.filename)
                             vim.fn.bufload(bufnr)
                         end
+                        breakpoints.set_enabled_at(bufnr, selection.lnum - 1, enabled)
+                    end
+
+                    local current_picker = action_state.get_current_picker(prompt_bufnr)
+                    current_picker:refresh(make_finder(all_breakpoints()), { reset_prompt = false })
+                end)
 
-                        -- Delete the breakpoint (using 0-indexed line)
-                        local deleted = breakpoints.delete_at(bufnr, selection.lnum - 1)
+                -- ctrl-g: put breakpoint in a group
+                map("i", "<C-g>", function()
+                    local selection = action_state.get_selected_entry()
+                    if not selection then
+                        return
+                    end
+
+                    local bp = selection.value.bp
+                    vim.ui.input({ prompt = "Breakpoint group: ", default = bp.group or "" }, function(name)
+                        if not name then
+                            return
+                        end
+                        name = vim.trim(name)
+                        if bp.symbol then
+                            breakpoints.set_function_group(bp.symbol, name)
+                        else
+                            local bufnr = vim.fn.bufnr(selection.filename)
+                            if bufnr == -1 then
+                                bufnr = vim.fn.bufadd(selection.filename)
+                                vim.fn.bufload(bufnr)
+                            end
+                            breakpoints.set_group_at(bufnr, selection.lnum - 1, name)
+                        end
+
+                        local current_picker = action_state.get_current_picker(prompt_bufnr)
+                        current_picker:refresh(make_finder(all_breakpoints()), { reset_prompt = false })
+                    end)
+                end)
+
+                -- ctrl-d: delete breakpoint
+                map("i", "<C-d>", function()
+                    local selection = action_state.get_selected_entry()
+                    if selection then
+                        local deleted
+                        if selection.value.bp.symbol then
+                            deleted = breakpoints.delete_function(selection.value.bp.symbol)
+                        else
+                            local bufnr = vim.fn.bufnr(selection.filename)
+
+                            -- Load buffer if not already loaded
+                            if bufnr == -1 then
+                                bufnr = vim.fn.bufadd(selection.filename)
+                                vim.fn.bufload(bufnr)
+                            end
+
+                            -- Delete the breakpoint (using 0-indexed line)
+                            deleted = breakpoints.delete_at(bufnr, selection.lnum - 1)
+                        end
 
                         if deleted then
                             -- Refresh the picker with updated breakpoint list
                             local current_picker = action_state.get_current_picker(prompt_bufnr)
                             current_picker:refresh(
-                                finders.new_table({
-                                    results = (function()
-                                        local updated_bp_list = breakpoints.get_all()
-                                        local updated_results = {}
-                                        for i, bp in ipairs(updated_bp_list) do
-                                            local filename = vim.fn.fnamemodify(bp.file, ":t")
-                                            local dir = vim.fn.fnamemodify(bp.file, ":h:t")
-                                            table.insert(updated_results, {
-                                                index = i,
-                                                file = bp.file,
-                                                line = bp.line,
-                                                display = string.format("%s/%s:%d", dir, filename, bp.line),
-                                                ordinal = bp.file .. ":" .. bp.line,
-                                            })
-                                        end
-                                        return updated_results
-                                    end)(),
-                                    entry_maker = function(entry)
-                                        return {
-                                            value = entry,
-                                            display = entry.display,
-                                            ordinal = entry.ordinal,
-                                            filename = entry.file,
-                                            lnum = entry.line,
-                                        }
-                                    end,
-                                }),
+                                make_finder(all_breakpoints()),
                                 { reset_prompt = false }
                             )
 
@@ -177,4 +273,440 @@ M.show_breakpoints = function(opts)
         :find()
 end
 
+-- Build picker entries from watchpoints.get_all() results
+local function make_watch_finder(wp_list)
+    local results = {}
+    for _, wp in ipairs(wp_list) do
+        local display = wp.kind .. " " .. wp.expression
+        if not wp.number then
+            display = display .. "  [pending]"
+        elseif wp.hits and wp.hits > 0 then
+            display = display .. "  " .. wp.hits .. (wp.hits == 1 and " hit" or " hits")
+        end
+        table.insert(results, { wp = wp, display = display })
+    end
+
+    return finders.new_table({
+        results = results,
+        entry_maker = function(entry)
+            return {
+                value = entry,
+                display = entry.display,
+                ordinal = entry.display,
+            }
+        end,
+    })
+end
+
+-- Show all watchpoints in a Telescope picker
+M.show_watchpoints = function(opts)
+    opts = opts or {}
+
+    local wp_list = watchpoints.get_all()
+
+    if #wp_list == 0 then
+        vim.notify("No watchpoints set", vim.log.levels.INFO)
+        return
+    end
+
+    pickers
+        .new(opts, {
+            prompt_title = "Watchpoints",
+            finder = make_watch_finder(wp_list),
+            sorter = conf.generic_sorter(opts),
+            attach_mappings = function(prompt_bufnr, map)
+                -- Watchpoints have no location to jump to
+                actions.select_default:replace(function()
+                    actions.close(prompt_bufnr)
+                end)
+
+                -- ctrl-d: delete watchpoint
+                map("i", "<C-d>", function()
+                    local selection = action_state.get_selected_entry()
+                    if selection and watchpoints.delete(selection.value.wp) then
+                        local current_picker = action_state.get_current_picker(prompt_bufnr)
+                        current_picker:refresh(make_watch_finder(watchpoints.get_all()), { reset_prompt = false })
+
+                        vim.notify("Deleted watchpoint " .. selection.display, vim.log.levels.INFO)
+                    end
+                end)
+
+                return true
+            end,
+        })
+        :find()
+end
+
+-- Pick the line to re-anchor an orphaned breakpoint on, from its best candidates
+local function pick_anchor(orphan, opts)
+    opts = opts or {}
+
+    local candidates = breakpoints.orphan_candidates(orphan)
+    if #candidates == 0 then
+        vim.notify("No similar lines in " .. short_location(orphan.file, orphan.line), vim.log.levels.INFO)
+        return
+    end
+
+    pickers
+        .new(opts, {
+            prompt_title = "Re-anchor " .. short_location(orphan.file, orphan.line),
+            finder = finders.new_table({
+                results = candidates,
+                entry_maker = function(candidate)
+                    local display = string.format(
+                        "%4d  %s  (%d%%)",
+                        candidate.line,
+                        candidate.text,
+                        math.floor(candidate.score * 100 + 0.5)
+                    )
+                    return {
+                        value = candidate,
+                        display = display,
+                        ordinal = display,
+                        filename = orphan.file,
+                        lnum = candidate.line,
+                    }
+                end,
+            }),
+            sorter = conf.generic_sorter(opts),
+            previewer = conf.grep_previewer(opts),
+            attach_mappings = function(prompt_bufnr)
+                actions.select_default:replace(function()
+                    local selection = action_state.get_selected_entry()
+                    actions.close(prompt_bufnr)
+                    if selection and breakpoints.reanchor_orphan(orphan, selection.lnum) then
+                        vim.notify(
+                            "Re-anchored breakpoint at " .. short_location(orphan.file, selection.lnum),
+                            vim.log.levels.INFO
+                        )
+                    end
+                end)
+                return true
+            end,
+        })
+        :find()
+end
+
+-- Build picker entries from breakpoints.get_orphans() results
+local function make_orphan_finder(orphans)
+    return finders.new_table({
+        results = orphans,
+        entry_maker = function(orphan)
+            local display = short_location(orphan.file, orphan.line)
+            if orphan.text then
+                display = display .. "  " .. orphan.text
+            end
+            display = display .. "  (" .. (orphan.reason or "not restored") .. ")"
+            return {
+                value = orphan,
+                display = display,
+                ordinal = display,
+            }
+        end,
+    })
+end
+
+-- Review breakpoints that couldn't be restored in a Telescope picker
+-- The preview shows the saved line and the most similar lines in the file now
+M.show_orphans = function(opts)
+    opts = opts or {}
+
+    local orphans = breakpoints.get_orphans()
+
+    if #orphans == 0 then
+        vim.notify("No orphaned breakpoints", vim.log.levels.INFO)
+        return
+    end
+
+    pickers
+        .new(opts, {
+            prompt_title = "Orphaned Breakpoints (<CR> re-anchor, <C-d> discard, <Esc> keep for later)",
+            finder = make_orphan_finder(orphans),
+            sorter = conf.generic_sorter(opts),
+            previewer = previewers.new_buffer_previewer({
+                title = "Saved line and candidates",
+                define_preview = function(self, entry)
+                    local orphan = entry.value
+                    local lines = {
+                        "Saved at " .. short_location(orphan.file, orphan.line) .. ":",
+                        "    " .. (orphan.text or "(line text not saved)"),
+                        "",
+                        "Candidates:",
+                    }
+                    for _, candidate in ipairs(breakpoints.orphan_candidates(orphan)) do
+                        table.insert(
+                            lines,
+                            string.format(
+                                "%6d  %3d%%  %s",
+                                candidate.line,
+                                math.floor(candidate.score * 100 + 0.5),
+                                candidate.text
+                            )
+                        )
+                    end
+                    if #lines == 4 then
+                        table.insert(lines, "    (none)")
+                    end
+                    vim.api.nvim_buf_set_lines(self.state.bufnr, 0, -1, false, lines)
+                end,
+            }),
+            attach_mappings = function(prompt_bufnr, map)
+                -- Default action: choose the line to re-anchor on
+                actions.select_default:replace(function()
+                    local selection = action_state.get_selected_entry()
+                    actions.close(prompt_bufnr)
+                    if selection then
+                        pick_anchor(selection.value, opts)
+                    end
+                end)
+
+                -- ctrl-d: discard orphan
+                map("i", "<C-d>", function()
+                    local selection = action_state.get_selected_entry()
+                    if selection and breakpoints.discard_orphan(selection.value) then
+                        local current_picker = action_state.get_current_picker(prompt_bufnr)
+                        current_picker:refresh(make_orphan_finder(breakpoints.get_orphans()), { reset_prompt = false })
+
+                        vim.notify("Discarded breakpoint at " .. selection.display, vim.log.levels.INFO)
+                    end
+                end)
+
+                return true
+            end,
+        })
+        :find()
+end
+
+-- Build picker entries from callstack.get_frames() results
+-- Library frames are dimmed, or left out when hide_library is set
+local function make_frame_finder(frames, hide_library)
+    local selected = callstack.get_selected()
+    local results = {}
+    for _, frame in ipairs(frames) do
+        local library = callstack.is_library(frame)
+        if not (library and hide_library) then
+            local marker = (selected and selected.level == frame.level) and "▸" or " "
+            local location
+            if frame.file and frame.line then
+                location = short_location(frame.file, frame.line)
+            else
+                location = frame.addr or "??"
+            end
+            local display = string.format("%s#%-3d %s  %s", marker, frame.level, frame.func or "??", location)
+            table.insert(results, { frame = frame, library = library, display = display })
+        end
+    end
+
+    return finders.new_table({
+        results = results,
+        entry_maker = function(entry)
+            return {
+                value = entry,
+                display = function()
+                    if entry.library then
+                        return entry.display, { { { 0, #entry.display }, "Comment" } }
+                    end
+                    return entry.display
+                end,
+                ordinal = entry.display .. " " .. (entry.frame.file or ""),
+                filename = entry.frame.file,
+                lnum = entry.frame.line,
+            }
+        end,
+    })
+end
+
+-- Show the stopped thread's frames in a Telescope picker and select one in GDB
+M.show_backtrace = function(opts)
+    opts = opts or {}
+
+    local function open()
+        local frames = callstack.get_frames()
+        if #frames == 0 then
+            vim.notify("The program isn't stopped", vim.log.levels.INFO)
+            return
+        end
+
+        local hide_library = false
+
+        pickers
+            .new(opts, {
+                prompt_title = "Backtrace (<CR> select frame, <C-h> hide/show library frames)",
+                finder = make_frame_finder(frames, hide_library),
+                sorter = conf.generic_sorter(opts),
+                previewer = previewers.new_buffer_previewer({
+                    title = "Frame Source",
+                    get_buffer_by_name = function(_, entry)
+                        return entry.filename
+                    end,
+                    define_preview = function(self, entry)
+                        if not entry.filename or vim.fn.filereadable(entry.filename) ~= 1 then
+                            vim.api.nvim_buf_set_lines(self.state.bufnr, 0, -1, false, {
+                                "No source for this frame",
+                            })
+                            return
+                        end
+
+                        conf.buffer_previewer_maker(entry.filename, self.state.bufnr, {
+                            bufname = self.state.bufname,
+                            winid = self.state.winid,
+                            callback = function(bufnr)
+                                local ns = vim.api.nvim_create_namespace("telescope_frame_preview_hl")
+                                pcall(vim.api.nvim_buf_clear_namespace, bufnr, ns, 0, -1)
+
+                                if entry.lnum then
+                                    pcall(vim.api.nvim_win_set_cursor, self.state.winid, { entry.lnum, 0 })
+                                    vim.api.nvim_win_call(self.state.winid, function()
+                                        vim.cmd("normal! zz")
+                                    end)
+                                    pcall(
+                                        vim.api.nvim_buf_add_highlight,
+                                        bufnr,
+                                        ns,
+                                        "TelescopePreviewLine",
+                                        entry.lnum - 1,
+                                        0,
+                                        -1
+                                    )
+                                end
+                            end,
+                        })
+                    end,
+                }),
+                attach_mappings = function(prompt_bufnr, map)
+                    -- Default action: select the frame in GDB and jump to it
+                    actions.select_default:replace(function()
+                        local selection = action_state.get_selected_entry()
+                        actions.close(prompt_bufnr)
+                        if selection then
+                            callstack.select(selection.value.frame.level)
+                        end
+                    end)
+
+                    -- ctrl-h: hide/show frames in std, core and external crates
+                    map("i", "<C-h>", function()
+                        hide_library = not hide_library
+                        local current_picker = action_state.get_current_picker(prompt_bufnr)
+                        current_picker:refresh(make_frame_finder(frames, hide_library), { reset_prompt = false })
+                    end)
+
+                    return true
+                end,
+            })
+            :find()
+    end
+
+    -- Read the frames again in case the stack changed in the GDB REPL
+    if not callstack.refresh(open) then
+        open()
+    end
+end
+
+-- Frames shown in the thread picker's preview
+local THREAD_PREVIEW_FRAMES = 8
+
+-- Preview lines for a thread's innermost frames
+local function thread_preview_lines(thread, frames)
+    local lines = { threads.label(thread) .. "  (" .. (thread.target_id or "") .. ")", "" }
+    if not frames then
+        table.insert(lines, "Frames not available (" .. (thread.state or "unknown") .. ")")
+        return lines
+    end
+    for _, frame in ipairs(frames) do
+        local location = (frame.file and frame.line) and short_location(frame.file, frame.line) or (frame.addr or "??")
+        table.insert(lines, string.format("#%-3d %s  %s", frame.level, frame.func or "??", location))
+    end
+    return lines
+end
+
+-- Show the program's threads in a Telescope picker and switch to one
+-- <CR> selects the thread, <C-l> selects it and pins the scheduler to it
+M.show_threads = function(opts)
+    opts = opts or {}
+
+    local listed = threads.list(function(thread_list)
+        if #thread_list == 0 then
+            vim.notify("No threads; is the program running?", vim.log.levels.INFO)
+            return
+        end
+
+        -- Frames by thread id, read from GDB when the thread is first previewed
+        local frame_cache = {}
+
+        pickers
+            .new(opts, {
+                prompt_title = "Threads (<CR> switch, <C-l> switch and pin)",
+                finder = finders.new_table({
+                    results = thread_list,
+                    entry_maker = function(thread)
+                        local display = string.format(
+                            "%s%-3d %-20s %s",
+                            thread.current and "▸" or " ",
+                            thread.id,
+                            threads.label(thread),
+                            thread.func or thread.state or ""
+                        )
+                        return {
+                            value = thread,
+                            display = display,
+                            ordinal = display,
+                        }
+                    end,
+                }),
+                sorter = conf.generic_sorter(opts),
+                previewer = previewers.new_buffer_previewer({
+                    title = "Thread Backtrace",
+                    define_preview = function(self, entry)
+                        local thread = entry.value
+                        local bufnr = self.state.bufnr
+                        if frame_cache[thread.id] ~= nil then
+                            vim.api.nvim_buf_set_lines(
+                                bufnr,
+                                0,
+                                -1,
+                                false,
+                                thread_preview_lines(thread, frame_cache[thread.id] or nil)
+                            )
+                            return
+                        end
+
+                        vim.api.nvim_buf_set_lines(bufnr, 0, -1, false, { "Reading frames..." })
+                        threads.frames(thread.id, THREAD_PREVIEW_FRAMES, function(frames)
+                            frame_cache[thread.id] = frames or false
+                            if vim.api.nvim_buf_is_valid(bufnr) then
+                                vim.api.nvim_buf_set_lines(bufnr, 0, -1, false, thread_preview_lines(thread, frames))
+                            end
+                        end)
+                    end,
+                }),
+                attach_mappings = function(prompt_bufnr, map)
+                    -- Default action: switch to the thread
+                    actions.select_default:replace(function()
+                        local selection = action_state.get_selected_entry()
+                        actions.close(prompt_bufnr)
+                        if selection then
+                            threads.select(selection.value.id, false)
+                        end
+                    end)
+
+                    -- ctrl-l: switch to the thread and lock the scheduler to it
+                    map("i", "<C-l>", function()
+                        local selection = action_state.get_selected_entry()
+                        actions.close(prompt_bufnr)
+                        if selection then
+                            threads.select(selection.value.id, true)
+                        end
+                    end)
+
+                    return true
+                end,
+            })
+            :find()
+    end)
+
+    if not listed then
+        vim.notify("No debug session is running", vim.log.levels.INFO)
+    end
+end
+
 return M
@@ -1,5 +1,9 @@
 local options = require("options")
 local breakpoints = require("breakpoints")
+local callstack = require("callstack")
+local mi = require("mi")
+local panics = require("panics")
+local watchpoints = require("watchpoints")
 
 local termdebug = {}
 
@@ -40,9 +44,17 @@ termdebug.start = function(binary_path, opts)
         vim.fn.TermDebugSendCommand(cmd)
     end
 
+    -- Open our own MI channel for reading state (eg hit counts) back from GDB
+    callstack.clear()
+    mi.start()
+
     -- Restore any breakpoints that were set before the debugger started
     vim.defer_fn(function()
         breakpoints.restore_all()
+        watchpoints.restore_all()
+
+        -- Stop on panics if enabled for this workspace
+        panics.restore()
     end, 100) -- Give termdebug time to fully initialize
 
     -- optionally move the cursor back to the original window
@@ -0,0 +1,74 @@
+-- Threads of the debugged program, read over the GDB/MI channel
+-- Lists threads with the names Rust gives them (std::thread::Builder::name)
+-- and switches between them, optionally pinning the scheduler
+
+local mi = require("mi")
+local callstack = require("callstack")
+local scheduler = require("scheduler")
+
+local threads = {}
+
+-- Thread from an MI -thread-info tuple
+local function thread_from_mi(thread, current_id)
+    local frame = thread.frame
+    return {
+        id = tonumber(thread.id),
+        name = thread.name,
+        target_id = thread["target-id"],
+        state = thread.state,
+        current = thread.id == current_id,
+        func = frame and callstack.demangle(frame.func),
+        file = frame and frame.fullname,
+        line = frame and tonumber(frame.line),
+    }
+end
+
+-- List the program's threads, like `info threads`
+-- callback receives { { id, name?, target_id, state, current, func?, file?, line? } }
+-- Returns false if the MI channel isn't active
+threads.list = function(callback)
+    return mi.send("-thread-info", function(record)
+        local list = {}
+        if record.class == "done" and record.results.threads then
+            local current_id = record.results["current-thread-id"]
+            for _, thread in ipairs(record.results.threads) do
+                table.insert(list, thread_from_mi(thread, current_id))
+            end
+        end
+        callback(list)
+    end)
+end
+
+-- Read the innermost count frames of a thread without selecting it
+-- callback receives the frames like callstack.get_frames(), or nil if GDB
+-- couldn't read them (eg the thread is running)
+threads.frames = function(id, count, callback)
+    local command = string.format("-stack-list-frames --thread %d 0 %d", id, count - 1)
+    return mi.send(command, function(record)
+        if record.class ~= "done" or not record.results.stack then
+            callback(nil)
+            return
+        end
+        local frames = {}
+        for _, frame in ipairs(record.results.stack) do
+            table.insert(frames, callstack.parse_frame(frame))
+        end
+        callback(frames)
+    end)
+end
+
+-- Switch GDB to a thread, and pin the scheduler to it if pin is set
+-- The call stack follows through GDB's thread-selected notification
+threads.select = function(id, pin)
+    vim.fn.TermDebugSendCommand("thread " .. id)
+    if pin then
+        scheduler.lock()
+    end
+end
+
+-- Short label for a thread: its name if Rust gave it one, or GDB's target id
+threads.label = function(thread)
+    return thread.name or thread.target_id or ("thread " .. thread.id)
+end
+
+return threads
@@ -0,0 +1,196 @@
+local mi = require("mi")
+
+local watchpoints = {}
+
+-- Active watchpoints in creation order: { expression, kind, number?, hits? }
+-- A watchpoint without a number is pending: it isn't set in GDB yet, usually
+-- because its expression isn't in scope (eg a local after reloading the binary)
+local watch_list = {}
+
+-- -break-watch flags and result tuple names by kind
+local WATCH_FLAGS = { watch = "", rwatch = "-r ", awatch = "-a " }
+local RESULT_KEYS = { watch = "wpt", rwatch = "hw-rwpt", awatch = "hw-awpt" }
+
+-- Kind by the breakpoint type GDB reports for watchpoints set in the REPL
+local KIND_BY_TYPE = {
+    ["watchpoint"] = "watch",
+    ["hw watchpoint"] = "watch",
+    ["read watchpoint"] = "rwatch",
+    ["acc watchpoint"] = "awatch",
+}
+
+local function find_number(number)
+    for i, wp in ipairs(watch_list) do
+        if wp.number == number then
+            return i, wp
+        end
+    end
+end
+
+local function is_tracked(wp)
+    return vim.tbl_contains(watch_list, wp)
+end
+
+-- Text of the last visual selection, joined into one line
+local function selected_text()
+    local start_pos = vim.fn.getpos("'<")
+    local end_pos = vim.fn.getpos("'>")
+    local lines = vim.api.nvim_buf_get_lines(0, start_pos[2] - 1, end_pos[2], false)
+    if #lines == 0 then
+        return nil
+    end
+    lines[#lines] = lines[#lines]:sub(1, end_pos[3])
+    lines[1] = lines[1]:sub(start_pos[3])
+    return vim.trim(table.concat(lines, " "))
+end
+
+-- Set a watchpoint in GDB; callback receives the error message if GDB refused it
+local function arm(wp, on_error)
+    if wp.arming then
+        return
+    end
+
+    local escaped = wp.expression:gsub('[\\"]', "\\%0")
+    local command = string.format('-break-watch %s"%s"', WATCH_FLAGS[wp.kind], escaped)
+    wp.arming = mi.send(command, function(record)
+        wp.arming = nil
+        local wpt = record.results[RESULT_KEYS[wp.kind]]
+        if record.class == "done" and wpt then
+            if is_tracked(wp) then
+                wp.number = wpt.number
+                wp.hits = 0
+            else
+                -- Deleted while GDB was setting it
+                pcall(vim.fn.TermDebugSendCommand, "delete " .. wpt.number)
+            end
+        elseif on_error then
+            on_error(record.results.msg or "unknown error")
+        end
+    end)
+end
+
+-- Try to set every pending watchpoint
+local function arm_pending()
+    for _, wp in ipairs(watch_list) do
+        if not wp.number then
+            arm(wp)
+        end
+    end
+end
+
+-- Watch an expression; kind is "watch" (write), "rwatch" (read) or "awatch" (access)
+-- Without an expression, watches the expression under the cursor
+watchpoints.create = function(kind, expression)
+    kind = kind or "watch"
+    expression = expression or vim.fn.expand("<cexpr>")
+    if expression == "" then
+        vim.notify("No expression to watch", vim.log.levels.WARN)
+        return
+    end
+
+    local wp = { expression = expression, kind = kind }
+    table.insert(watch_list, wp)
+
+    if not mi.is_active() then
+        vim.notify("Watchpoint on " .. expression .. " will be set when debugging starts", vim.log.levels.INFO)
+        return
+    end
+
+    arm(wp, function(msg)
+        watchpoints.delete(wp)
+        vim.notify("Could not watch " .. expression .. ": " .. msg, vim.log.levels.ERROR)
+    end)
+end
+
+-- Watch the visual selection
+watchpoints.create_from_selection = function(kind)
+    watchpoints.create(kind, selected_text())
+end
+
+-- Delete a watchpoint (as returned by get_all)
+watchpoints.delete = function(wp)
+    for i, tracked in ipairs(watch_list) do
+        if tracked == wp or (wp.number and tracked.number == wp.number) then
+            table.remove(watch_list, i)
+            if tracked.number then
+                pcall(vim.fn.TermDebugSendCommand, "delete " .. tracked.number)
+            end
+            return true
+        end
+    end
+    return false
+end
+
+-- Delete all watchpoints
+watchpoints.delete_all = function()
+    for _, wp in ipairs(watch_list) do
+        if wp.number then
+            pcall(vim.fn.TermDebugSendCommand, "delete " .. wp.number)
+        end
+    end
+    watch_list = {}
+end
+
+-- Get all watchpoints (returned entries can be passed to watchpoints.delete)
+watchpoints.get_all = function()
+    return vim.list_extend({}, watch_list)
+end
+
+-- Forget a watchpoint GDB deleted because its frame returned
+watchpoints.expire = function(number)
+    local i, wp = find_number(number)
+    if not i then
+        return false
+    end
+    table.remove(watch_list, i)
+    vim.notify("Watchpoint on " .. wp.expression .. " went out of scope", vim.log.levels.INFO)
+    return true
+end
+
+-- Set all watchpoints again, eg after GDB's breakpoints were deleted for a reload
+-- Ones that aren't in scope yet stay pending and are retried whenever the program stops
+watchpoints.restore_all = function()
+    for _, wp in ipairs(watch_list) do
+        wp.number = nil
+        wp.hits = nil
+        -- A restarted MI channel drops the replies to arms still in flight
+        wp.arming = nil
+    end
+    arm_pending()
+end
+
+-- Watchpoints set in the GDB REPL are tracked too; hit counts update on each trigger
+local function on_watchpoint_changed(record)
+    local bkpt = record.results.bkpt
+    local kind = bkpt and KIND_BY_TYPE[bkpt.type]
+    if not kind or not bkpt.number then
+        return
+    end
+
+    local _, wp = find_number(bkpt.number)
+    if not wp then
+        wp = { expression = bkpt.what or bkpt.exp, kind = kind, number = bkpt.number }
+        table.insert(watch_list, wp)
+    end
+    wp.hits = tonumber(bkpt.times) or wp.hits
+end
+
+mi.on("breakpoint-created", on_watchpoint_changed)
+mi.on("breakpoint-modified", on_watchpoint_changed)
+
+-- Watchpoints deleted in the GDB REPL are forgotten
+mi.on("breakpoint-deleted", function(record)
+    local i = find_number(record.results.id)
+    if i then
+        table.remove(watch_list, i)
+    end
+end)
+
+mi.on("stopped", function(record)
+    if record.results.reason == "watchpoint-scope" then
+        watchpoints.expire(record.results.wpnum)
+    end
+    arm_pending()
+end)
+
+return watchpoints
@@ -0,0 +1,114 @@
+local state_file = require("state_file")
+
+local workspace = {}
+
+-- Workspace roots by working directory, since cargo metadata is slow on big workspaces
+local root_cache = {}
+
+-- Get the cargo workspace root, or the current directory outside a cargo workspace
+workspace.root = function()
+    local cwd = vim.fn.getcwd()
+    if root_cache[cwd] then
+        return root_cache[cwd]
+    end
+
+    -- Fallback to current directory if not in a cargo workspace
+    local root = cwd
+
+    -- Try to get the cargo workspace root
+    local metadata_json = vim.fn.system("cargo metadata --no-deps --format-version=1 2>/dev/null")
+    if vim.v.shell_error == 0 then
+        local ok, metadata = pcall(vim.json.decode, metadata_json)
+        if ok and metadata and metadata.workspace_root then
+            root = metadata.workspace_root
+        end
+    end
+
+    root_cache[cwd] = root
+    return root
+end
+
+-- Make a path relative to the workspace root; paths outside the workspace stay absolute
+workspace.relative_path = function(path, root)
+    root = root or workspace.root()
+    if path:sub(1, #root + 1) == root .. "/" then
+        return path:sub(#root + 2)
+    end
+    return path
+end
+
+-- Resolve a path stored relative to the workspace root; absolute paths are returned as is
+workspace.absolute_path = function(path, root)
+    if path:sub(1, 1) == "/" then
+        return path
+    end
+    return (root or workspace.root()) .. "/" .. path
+end
+
+-- Get the workspace-local state directory (.rust-termdebug.nvim in the workspace root)
+workspace.state_dir = function()
+    -- Create .rust-termdebug.nvim directory if it doesn't exist
+    local dir = workspace.root() .. "/.rust-termdebug.nvim"
+    if vim.fn.isdirectory(dir) == 0 then
+        vim.fn.mkdir(dir, "p")
+    end
+
+    return dir
+end
+
+-- Run a git command in the workspace root; returns the first line of output or nil on failure
+local function git(args)
+    local output = vim.fn.systemlist(vim.list_extend({ "git", "-C", workspace.root() }, args))
+    if vim.v.shell_error ~= 0 or not output[1] or output[1] == "" then
+        return nil
+    end
+    return output[1]
+end
+
+-- Get the current git branch, the short commit hash for a detached HEAD,
+-- or nil outside a git repository. Each worktree has its own HEAD.
+workspace.branch = function()
+    local branch = git({ "rev-parse", "--abbrev-ref", "HEAD" })
+    if branch == "HEAD" then
+        return git({ "rev-parse", "--short", "HEAD" })
+    end
+    return branch
+end
+
+-- Get the git directory holding HEAD for the current worktree, or nil outside a git repository
+workspace.git_dir = function()
+    return git({ "rev-parse", "--absolute-git-dir" })
+end
+
+-- Read all per-workspace settings from .rust-termdebug.nvim/settings.json
+local function read_settings(path)
+    local file = io.open(path, "r")
+    if not file then
+        return {}
+    end
+
+    local content = file:read("*a")
+    file:close()
+
+    local ok, settings = pcall(vim.json.decode, content)
+    if not ok or type(settings) ~= "table" then
+        return {}
+    end
+    return settings
+end
+
+-- Get a per-workspace setting, or nil if it was never set
+workspace.get_setting = function(key)
+    return read_settings(workspace.state_dir() .. "/settings.json")[key]
+end
+
+-- Store a per-workspace setting
+workspace.set_setting = function(key, value)
+    local path = workspace.state_dir() .. "/settings.json"
+    local settings = read_settings(path)
+    settings[key] = value
+
+    state_file.write_sync(path, vim.json.encode(settings))
+end
+
+return workspace
@@ -3,6 +3,7 @@
 
 describe("breakpoints module", function()
     local breakpoints
+    local fake_mi = require("helpers.fake_mi")
     local test_files = {}
 
     -- Helper to create a temp test file and track it for cleanup
@@ -45,6 +46,8 @@ describe("breakpoints module", function()
     end)
 
     after_each(function()
+        fake_mi.restore()
+
         -- Clean up files (using pcall to ensure it runs even if tests fail)
         cleanup_files()
 
@@ -195,6 +198,668 @@ describe("breakpoints module", function()
         end)
     end)
 
+    describe("conditional breakpoints", function()
+        local original_input
+
+        before_each(function()
+            original_input = vim.ui.input
+        end)
+
+        after_each(function()
+            vim.ui.input = original_input
+        end)
+
+        it("should create a breakpoint with the entered condition", function()
+            vim.ui.input = function(_, on_confirm)
+                on_confirm("x > 40")
+            end
+
+            vim.api.nvim_win_set_cursor(0, { 3, 0 })
+            breakpoints.create_conditional()
+
+            local bps = breakpoints.get_all()
+            assert.equals(1, #bps)
+            assert.equals(3, bps[1].line)
+            assert.equals("x > 40", bps[1].condition)
+        end)
+
+        it("should keep the condition when creating a breakpoint on the same line", function()
+            vim.ui.input = function(_, on_confirm)
+                on_confirm("x > 40")
+            end
+
+            vim.api.nvim_win_set_cursor(0, { 3, 0 })
+            breakpoints.create_conditional()
+
+            local original_send_command = vim.fn.TermDebugSendCommand
+            local original_cmd = vim.cmd
+            local sent = {}
+            vim.fn.TermDebugSendCommand = function(command)
+                table.insert(sent, command)
+            end
+            vim.cmd = function(command)
+                if command == "Break" then
+                    table.insert(sent, command)
+                else
+                    original_cmd(command)
+                end
+            end
+            local ok, err = pcall(breakpoints.create)
+            vim.fn.TermDebugSendCommand = original_send_command
+            vim.cmd = original_cmd
+            assert(ok, err)
+
+            local bps = breakpoints.get_all()
+            assert.equals(1, #bps)
+            assert.equals("x > 40", bps[1].condition)
+            assert.same({}, sent)
+        end)
+
+        it("should keep the breakpoint's id and group when GDB replaces it", function()
+            -- Reload under the fake so its MI handlers can be driven
+            fake_mi.install()
+            package.loaded["breakpoints"] = nil
+            breakpoints = require("breakpoints")
+
+            vim.api.nvim_win_set_cursor(0, { 3, 0 })
+            breakpoints.create()
+            breakpoints.set_group_at(vim.api.nvim_get_current_buf(), 2, "auth")
+            fake_mi.emit("breakpoint-created", {
+                bkpt = { number = "1", type = "breakpoint", fullname = test_files[1], line = "3" },
+            })
+            local id = breakpoints.get_all()[1].id
+
+            vim.ui.input = function(_, on_confirm)
+                on_confirm("x > 40")
+            end
+            breakpoints.create_conditional()
+
+            -- GDB deletes the old breakpoint and creates the one with the condition
+            fake_mi.emit("breakpoint-deleted", { id = "1" })
+            fake_mi.emit("breakpoint-created", {
+                bkpt = { number = "2", type = "breakpoint", fullname = test_files[1], line = "3", cond = "x > 40" },
+            })
+
+            local bps = breakpoints.get_all()
+            assert.equals(1, #bps)
+            assert.equals("x > 40", bps[1].condition)
+            assert.equals("auth", bps[1].group)
+            assert.equals(id, bps[1].id)
+        end)
+
+        it("should not create a breakpoint when input is cancelled", function()
+            vim.ui.input = function(_, on_confirm)
+                on_confirm(nil)
+            end
+
+            vim.api.nvim_win_set_cursor(0, { 3, 0 })
+            breakpoints.create_conditional()
+
+            assert.equals(0, #breakpoints.get_all())
+        end)
+
+        it("should persist the condition to disk", function()
+            local persist_config = { enabled = true, line_locator = "exact" }
+            breakpoints.set_persistence(persist_config)
+
+            vim.ui.input = function(_, on_confirm)
+                on_confirm("y == 43")
+            end
+
+            vim.api.nvim_win_set_cursor(0, { 4, 0 })
+            breakpoints.create_conditional()
+
+            -- Simulate a restart by reloading the module
+            package.loaded["breakpoints"] = nil
+            breakpoints = require("breakpoints")
+            breakpoints.set_persistence(persist_config)
+            breakpoints.load_from_disk()
+
+            local bps = breakpoints.get_all()
+            assert.equals(1, #bps)
+            assert.equals(4, bps[1].line)
+            assert.equals("y == 43", bps[1].condition)
+
+            breakpoints.delete_all()
+            breakpoints.set_persistence(nil)
+        end)
+    end)
+
+    describe("logpoints", function()
+        local original_input
+
+        before_each(function()
+            original_input = vim.ui.input
+        end)
+
+        after_each(function()
+            vim.ui.input = original_input
+        end)
+
+        it("should create a logpoint with the entered message", function()
+            vim.ui.input = function(_, on_confirm)
+                on_confirm('"x = %d\\n", x')
+            end
+
+            vim.api.nvim_win_set_cursor(0, { 3, 0 })
+            breakpoints.create_logpoint()
+
+            local bps = breakpoints.get_all()
+            assert.equals(1, #bps)
+            assert.equals("logpoint", bps[1].kind)
+            assert.equals('"x = %d\\n", x', bps[1].message)
+        end)
+
+        it("should persist logpoints to disk", function()
+            local persist_config = { enabled = true, line_locator = "exact" }
+            breakpoints.set_persistence(persist_config)
+
+            vim.ui.input = function(_, on_confirm)
+                on_confirm('"y = %d\\n", y')
+            end
+
+            vim.api.nvim_win_set_cursor(0, { 5, 0 })
+            breakpoints.create_logpoint()
+
+            -- Simulate a restart by reloading the module
+            package.loaded["breakpoints"] = nil
+            breakpoints = require("breakpoints")
+            breakpoints.set_persistence(persist_config)
+            breakpoints.load_from_disk()
+
+            local bps = breakpoints.get_all()
+            assert.equals(1, #bps)
+            assert.equals(5, bps[1].line)
+            assert.equals("logpoint", bps[1].kind)
+            assert.equals('"y = %d\\n", y', bps[1].message)
+
+            breakpoints.delete_all()
+            breakpoints.set_persistence(nil)
+        end)
+    end)
+
+    describe("hit count breakpoints", function()
+        local original_input
+
+        before_each(function()
+            original_input = vim.ui.input
+        end)
+
+        after_each(function()
+            vim.ui.input = original_input
+        end)
+
+        it("should store the hit number as an ignore count", function()
+            vim.ui.input = function(_, on_confirm)
+                on_confirm("50")
+            end
+
+            vim.api.nvim_win_set_cursor(0, { 3, 0 })
+            breakpoints.create_hit_count()
+
+            local bps = breakpoints.get_all()
+            assert.equals(1, #bps)
+            assert.equals(49, bps[1].ignore_count)
+        end)
+
+        it("should keep an existing condition when adding a hit count", function()
+            vim.api.nvim_win_set_cursor(0, { 3, 0 })
+
+            vim.ui.input = function(_, on_confirm)
+                on_confirm("x > 40")
+            end
+            breakpoints.create_conditional()
+
+            vim.ui.input = function(_, on_confirm)
+                on_confirm("3")
+            end
+            breakpoints.create_hit_count()
+
+            local bps = breakpoints.get_all()
+            assert.equals(1, #bps)
+            assert.equals("x > 40", bps[1].condition)
+            assert.equals(2, bps[1].ignore_count)
+        end)
+
+        it("should reject a non-numeric hit number", function()
+            vim.ui.input = function(_, on_confirm)
+                on_confirm("lots")
+            end
+
+            vim.api.nvim_win_set_cursor(0, { 3, 0 })
+            breakpoints.create_hit_count()
+
+            assert.equals(0, #breakpoints.get_all())
+        end)
+
+        it("should persist the ignore count to disk", function()
+            local persist_config = { enabled = true, line_locator = "exact" }
+            breakpoints.set_persistence(persist_config)
+
+            vim.ui.input = function(_, on_confirm)
+                on_confirm("10")
+            end
+
+            vim.api.nvim_win_set_cursor(0, { 4, 0 })
+            breakpoints.create_hit_count()
+
+            -- Simulate a restart by reloading the module
+            package.loaded["breakpoints"] = nil
+            breakpoints = require("breakpoints")
+            breakpoints.set_persistence(persist_config)
+            breakpoints.load_from_disk()
+
+            local bps = breakpoints.get_all()
+            assert.equals(1, #bps)
+            assert.equals(9, bps[1].ignore_count)
+
+            breakpoints.delete_all()
+            breakpoints.set_persistence(nil)
+        end)
+    end)
+
+    describe("gdb sync", function()
+        it("should create extmarks for breakpoints set in GDB", function()
+            breakpoints.sync_from_gdb({
+                { number = "1", type = "breakpoint", fullname = test_files[1], line = "4", times = "0" },
+            })
+
+            local bps = breakpoints.get_all()
+            assert.equals(1, #bps)
+            assert.equals(4, bps[1].line)
+        end)
+
+        it("should import dprintf breakpoints as logpoints", function()
+            breakpoints.sync_from_gdb({
+                {
+                    number = "2",
+                    type = "dprintf",
+                    fullname = test_files[1],
+                    line = "3",
+                    script = { 'printf "x = %d\\n", x' },
+                },
+            })
+
+            local bps = breakpoints.get_all()
+            assert.equals(1, #bps)
+            assert.equals("logpoint", bps[1].kind)
+            assert.equals('"x = %d\\n", x', bps[1].message)
+        end)
+
+        it("should track hit counts of existing breakpoints", function()
+            vim.api.nvim_win_set_cursor(0, { 3, 0 })
+            breakpoints.create()
+
+            breakpoints.sync_from_gdb({
+                { number = "1", type = "breakpoint", fullname = test_files[1], line = "3", times = "7" },
+            })
+
+            local bps = breakpoints.get_all()
+            assert.equals(1, #bps)
+            assert.equals(7, bps[1].hits)
+        end)
+
+        it("should remove extmarks for breakpoints deleted in GDB", function()
+            vim.api.nvim_win_set_cursor(0, { 3, 0 })
+            breakpoints.create()
+            vim.api.nvim_win_set_cursor(0, { 5, 0 })
+            breakpoints.create()
+
+            breakpoints.sync_from_gdb({
+                { number = "1", type = "breakpoint", fullname = test_files[1], line = "3" },
+                { number = "2", type = "breakpoint", fullname = test_files[1], line = "5" },
+            })
+            assert.equals(2, #breakpoints.get_all())
+
+            -- Breakpoint 1 was deleted in the GDB REPL
+            breakpoints.sync_from_gdb({
+                { number = "2", type = "breakpoint", fullname = test_files[1], line = "5" },
+            })
+
+            local bps = breakpoints.get_all()
+            assert.equals(1, #bps)
+            assert.equals(5, bps[1].line)
+        end)
+
+        it("should keep breakpoints GDB doesn't know about yet", function()
+            vim.api.nvim_win_set_cursor(0, { 3, 0 })
+            breakpoints.create()
+
+            breakpoints.sync_from_gdb({})
+
+            assert.equals(1, #breakpoints.get_all())
+        end)
+
+        it("should ignore watchpoints and breakpoints without readable source", function()
+            breakpoints.sync_from_gdb({
+                { number = "1", type = "hw watchpoint", what = "x" },
+                { number = "2", type = "breakpoint", fullname = "/rustc/abc/library/std/src/panicking.rs", line = "10" },
+            })
+
+            assert.equals(0, #breakpoints.get_all())
+        end)
+    end)
+
+    describe("gdb resolution", function()
+        local function sign_text()
+            local ns = vim.api.nvim_get_namespaces()["rust_termdebug_breakpoints"]
+            local marks = vim.api.nvim_buf_get_extmarks(0, ns, 0, -1, { details = true })
+            return vim.trim(marks[1][4].sign_text)
+        end
+
+        it("should move the extmark to the line GDB resolved", function()
+            vim.api.nvim_win_set_cursor(0, { 1, 0 })
+            breakpoints.create()
+
+            breakpoints.sync_from_gdb({
+                {
+                    number = "1",
+                    type = "breakpoint",
+                    fullname = test_files[1],
+                    line = "2",
+                    addr = "0x0000555555559b0c",
+                    ["original-location"] = test_files[1] .. ":1",
+                },
+            })
+
+            local bps = breakpoints.get_all()
+            assert.equals(1, #bps)
+            assert.equals(2, bps[1].line)
+            assert.equals("0x0000555555559b0c", bps[1].address)
+        end)
+
+        it("should not move extmarks back after the code was edited", function()
+            vim.api.nvim_win_set_cursor(0, { 3, 0 })
+            breakpoints.create()
+            local resolved = {
+                { number = "1", type = "breakpoint", fullname = test_files[1], line = "3", addr = "0x10" },
+            }
+            breakpoints.sync_from_gdb(resolved)
+
+            vim.api.nvim_buf_set_lines(0, 0, 0, false, { "// new first line" })
+            breakpoints.sync_from_gdb(resolved)
+
+            assert.equals(4, breakpoints.get_all()[1].line)
+        end)
+
+        it("should flag pending breakpoints until GDB resolves them", function()
+            vim.api.nvim_win_set_cursor(0, { 3, 0 })
+            breakpoints.create()
+
+            breakpoints.sync_from_gdb({
+                { number = "1", type = "breakpoint", addr = "<PENDING>", pending = test_files[1] .. ":3" },
+            })
+
+            assert.is_true(breakpoints.get_all()[1].unresolved)
+            assert.equals("◌", sign_text())
+
+            breakpoints.sync_from_gdb({
+                { number = "1", type = "breakpoint", fullname = test_files[1], line = "3", addr = "0x10" },
+            })
+
+            assert.is_nil(breakpoints.get_all()[1].unresolved)
+            assert.equals("●", sign_text())
+        end)
+
+        it("should flag breakpoints GDB has no record of", function()
+            vim.api.nvim_win_set_cursor(0, { 3, 0 })
+            breakpoints.create()
+
+            breakpoints.sync_from_gdb({}, { mark_missing = true })
+
+            assert.is_true(breakpoints.get_all()[1].unresolved)
+        end)
+
+        it("should forget GDB's resolution when the session ends", function()
+            vim.api.nvim_win_set_cursor(0, { 3, 0 })
+            breakpoints.create()
+            breakpoints.sync_from_gdb({}, { mark_missing = true })
+
+            vim.api.nvim_exec_autocmds("User", { pattern = "TermdebugStopPost" })
+
+            assert.is_nil(breakpoints.get_all()[1].unresolved)
+        end)
+    end)
+
+    describe("function breakpoints", function()
+        it("should track breakpoints by symbol", function()
+            breakpoints.add_function("test_project::parse")
+
+            local fns = breakpoints.get_functions()
+            assert.equals(1, #fns)
+            assert.equals("test_project::parse", fns[1].symbol)
+
+            -- Function breakpoints aren't line breakpoints
+            assert.equals(0, #breakpoints.get_all())
+        end)
+
+        it("should not add the same symbol twice", function()
+            breakpoints.add_function("test_project::parse")
+            breakpoints.add_function("test_project::parse")
+
+            assert.equals(1, #breakpoints.get_functions())
+        end)
+
+        it("should delete a function breakpoint", function()
+            breakpoints.add_function("test_project::parse")
+
+            assert.is_true(breakpoints.delete_function("test_project::parse"))
+            assert.equals(0, #breakpoints.get_functions())
+            assert.is_false(breakpoints.delete_function("test_project::parse"))
+        end)
+
+        it("should learn the resolved location from GDB without adding an extmark", function()
+            breakpoints.add_function("main")
+
+            breakpoints.sync_from_gdb({
+                {
+                    number = "1",
+                    type = "breakpoint",
+                    fullname = test_files[1],
+                    line = "2",
+                    times = "1",
+                    ["original-location"] = "main",
+                },
+            })
+
+            local fns = breakpoints.get_functions()
+            assert.equals(test_files[1], fns[1].file)
+            assert.equals(2, fns[1].line)
+            assert.equals(1, fns[1].hits)
+            assert.equals(0, #breakpoints.get_all())
+        end)
+
+        it("should persist function breakpoints by symbol", function()
+            local persist_config = { enabled = true, line_locator = "exact" }
+            breakpoints.set_persistence(persist_config)
+
+            breakpoints.add_function("test_project::parse")
+
+            -- Simulate a restart by reloading the module
+            package.loaded["breakpoints"] = nil
+            breakpoints = require("breakpoints")
+            breakpoints.set_persistence(persist_config)
+            breakpoints.load_from_disk()
+
+            local fns = breakpoints.get_functions()
+            assert.equals(1, #fns)
+            assert.equals("test_project::parse", fns[1].symbol)
+
+            breakpoints.delete_all()
+            breakpoints.set_persistence(nil)
+        end)
+    end)
+
+    describe("enable and disable", function()
+        local function sign_text()
+            local ns = vim.api.nvim_get_namespaces()["rust_termdebug_breakpoints"]
+            local marks = vim.api.nvim_buf_get_extmarks(0, ns, 0, -1, { details = true })
+            return marks[1][4].sign_text
+        end
+
+        it("should disable a breakpoint without deleting it", function()
+            vim.api.nvim_win_set_cursor(0, { 3, 0 })
+            breakpoints.create()
+
+            breakpoints.toggle_enabled()
+
+            local bps = breakpoints.get_all()
+            assert.equals(1, #bps)
+            assert.is_false(bps[1].enabled)
+            assert.equals("○", vim.trim(sign_text()))
+
+            breakpoints.toggle_enabled()
+
+            bps = breakpoints.get_all()
+            assert.is_nil(bps[1].enabled)
+            assert.equals("●", vim.trim(sign_text()))
+        end)
+
+        it("should keep the condition of a disabled breakpoint", function()
+            local original_input = vim.ui.input
+            vim.ui.input = function(_, on_confirm)
+                on_confirm("x > 40")
+            end
+            vim.api.nvim_win_set_cursor(0, { 3, 0 })
+            breakpoints.create_conditional()
+            vim.ui.input = original_input
+
+            breakpoints.set_enabled_at(vim.api.nvim_get_current_buf(), 2, false)
+            breakpoints.set_enabled_at(vim.api.nvim_get_current_buf(), 2, true)
+
+            local bps = breakpoints.get_all()
+            assert.equals("x > 40", bps[1].condition)
+            assert.equals("◆", vim.trim(sign_text()))
+        end)
+
+        it("should pick up breakpoints disabled in GDB", function()
+            vim.api.nvim_win_set_cursor(0, { 3, 0 })
+            breakpoints.create()
+
+            breakpoints.sync_from_gdb({
+                { number = "1", type = "breakpoint", fullname = test_files[1], line = "3", enabled = "n" },
+            })
+
+            assert.is_false(breakpoints.get_all()[1].enabled)
+        end)
+
+        it("should persist the disabled state", function()
+            local persist_config = { enabled = true, line_locator = "exact" }
+            breakpoints.set_persistence(persist_config)
+
+            vim.api.nvim_win_set_cursor(0, { 4, 0 })
+            breakpoints.create()
+            breakpoints.toggle_enabled()
+            breakpoints.add_function("test_project::parse")
+            breakpoints.set_function_enabled("test_project::parse", false)
+
+            -- Simulate a restart by reloading the module
+            package.loaded["breakpoints"] = nil
+            breakpoints = require("breakpoints")
+            breakpoints.set_persistence(persist_config)
+            breakpoints.load_from_disk()
+
+            assert.is_false(breakpoints.get_all()[1].enabled)
+            assert.is_false(breakpoints.get_functions()[1].enabled)
+
+            breakpoints.delete_all()
+            breakpoints.set_persistence(nil)
+        end)
+    end)
+
+    describe("breakpoint groups", function()
+        local bufnr
+
+        before_each(function()
+            bufnr = vim.api.nvim_get_current_buf()
+            vim.api.nvim_win_set_cursor(0, { 3, 0 })
+            breakpoints.create()
+            vim.api.nvim_win_set_cursor(0, { 5, 0 })
+            breakpoints.create()
+            breakpoints.set_group_at(bufnr, 2, "parser-bug")
+            breakpoints.set_group_at(bufnr, 4, "parser-bug")
+            vim.api.nvim_win_set_cursor(0, { 4, 0 })
+            breakpoints.create()
+        end)
+
+        local function lines_in_group(name)
+            local lines = {}
+            for _, bp in ipairs(breakpoints.get_all()) do
+                if bp.group == name then
+                    table.insert(lines, bp.line)
+                end
+            end
+            table.sort(lines)
+            return lines
+        end
+
+        it("should list group names", function()
+            breakpoints.add_function("test_project::parse")
+            breakpoints.set_function_group("test_project::parse", "auth-flow")
+
+            assert.same({ "auth-flow", "parser-bug" }, breakpoints.get_groups())
+            assert.same({ 3, 5 }, lines_in_group("parser-bug"))
+        end)
+
+        it("should disable and enable a whole group", function()
+            breakpoints.disable_group("parser-bug")
+
+            for _, bp in ipairs(breakpoints.get_all()) do
+                if bp.group == "parser-bug" then
+                    assert.is_false(bp.enabled)
+                else
+                    assert.is_nil(bp.enabled)
+                end
+            end
+
+            breakpoints.enable_group("parser-bug")
+
+            for _, bp in ipairs(breakpoints.get_all()) do
+                assert.is_nil(bp.enabled)
+            end
+        end)
+
+        it("should park a deleted group and restore it", function()
+            breakpoints.delete_group("parser-bug")
+
+            assert.equals(1, #breakpoints.get_all())
+            assert.same({ "parser-bug" }, breakpoints.get_groups())
+
+            breakpoints.restore_group("parser-bug")
+
+            assert.equals(3, #breakpoints.get_all())
+            assert.same({ 3, 5 }, lines_in_group("parser-bug"))
+        end)
+
+        it("should forget a group deleted twice", function()
+            breakpoints.delete_group("parser-bug")
+            breakpoints.delete_group("parser-bug")
+
+            assert.same({}, breakpoints.get_groups())
+            breakpoints.restore_group("parser-bug")
+            assert.equals(1, #breakpoints.get_all())
+        end)
+
+        it("should keep parked groups in the persistence file", function()
+            local persist_config = { enabled = true, line_locator = "exact" }
+            breakpoints.set_persistence(persist_config)
+            breakpoints.delete_group("parser-bug")
+
+            -- Simulate a restart by reloading the module
+            package.loaded["breakpoints"] = nil
+            breakpoints = require("breakpoints")
+            breakpoints.set_persistence(persist_config)
+            breakpoints.load_from_disk()
+
+            assert.equals(1, #breakpoints.get_all())
+            breakpoints.restore_group("parser-bug")
+            assert.same({ 3, 5 }, lines_in_group("parser-bug"))
+
+            breakpoints.delete_all()
+            breakpoints.set_persistence(nil)
+        end)
+    end)
+
     describe("breakpoint persistence", function()
         local persist_config = { enabled = true, line_locator = "exact" }
 
@@ -263,6 +928,72 @@ describe("breakpoints module", function()
         end)
     end)
 
+    describe("per-branch persistence", function()
+        local workspace = require("workspace")
+        local original_branch
+        local persist_config = { enabled = true, line_locator = "exact", per_branch = true }
+
+        before_each(function()
+            original_branch = workspace.branch
+        end)
+
+        after_each(function()
+            workspace.branch = original_branch
+            breakpoints.delete_all()
+            breakpoints.set_persistence(nil)
+            require("state_file").flush()
+            vim.fn.delete(workspace.state_dir() .. "/branches", "rf")
+        end)
+
+        local function on_branch(name)
+            workspace.branch = function()
+                return name
+            end
+        end
+
+        local function lines()
+            local result = {}
+            for _, bp in ipairs(breakpoints.get_all()) do
+                table.insert(result, bp.line)
+            end
+            table.sort(result)
+            return result
+        end
+
+        it("should save each branch's breakpoints separately", function()
+            on_branch("spec-main")
+            breakpoints.set_persistence(persist_config)
+            vim.api.nvim_win_set_cursor(0, { 3, 0 })
+            breakpoints.create()
+
+            assert.is_not_nil(require("state_file").read(workspace.state_dir() .. "/branches/spec-main.json"))
+        end)
+
+        it("should swap breakpoint sets when the branch changes", function()
+            on_branch("spec-main")
+            breakpoints.set_persistence(persist_config)
+            vim.api.nvim_win_set_cursor(0, { 3, 0 })
+            breakpoints.create()
+
+            -- A new branch starts with the current breakpoints
+            on_branch("spec/feature")
+            breakpoints.check_branch()
+            assert.same({ 3 }, lines())
+
+            vim.api.nvim_win_set_cursor(0, { 5, 0 })
+            breakpoints.create()
+            assert.same({ 3, 5 }, lines())
+
+            on_branch("spec-main")
+            breakpoints.check_branch()
+            assert.same({ 3 }, lines())
+
+            on_branch("spec/feature")
+            breakpoints.check_branch()
+            assert.same({ 3, 5 }, lines())
+        end)
+    end)
+
     describe("get_all", function()
         it("should return empty table when no breakpoints exist", function()
             local bps = breakpoints.get_all()
@@ -0,0 +1,161 @@
+-- Tests for the call stack model and frame highlights
+-- Run with: nvim --headless -c "PlenaryBustedDirectory tests/ {minimal_init = 'tests/minimal_init.lua'}"
+
+describe("callstack module", function()
+    local callstack
+    local fake_mi = require("helpers.fake_mi")
+    local file
+    local ns
+
+    before_each(function()
+        package.loaded["mi"] = nil
+        package.loaded["callstack"] = nil
+        callstack = require("callstack")
+        ns = vim.api.nvim_get_namespaces()["rust_termdebug_callstack"]
+
+        file = vim.fn.tempname() .. ".rs"
+        vim.fn.writefile({
+            "fn main() {",
+            "    run();",
+            "}",
+            "fn run() {",
+            "    step();",
+            "}",
+            "fn step() {",
+            "    let x = 1;",
+            "}",
+        }, file)
+        vim.cmd("edit " .. vim.fn.fnameescape(file))
+    end)
+
+    after_each(function()
+        fake_mi.restore()
+        callstack.clear()
+        vim.cmd("bwipeout!")
+        vim.fn.delete(file)
+    end)
+
+    local function frame(level, func, line)
+        return { level = tostring(level), func = func, fullname = file, line = tostring(line), addr = "0x" .. level }
+    end
+
+    local function stack()
+        return { frame(0, "step", 8), frame(1, "run", 5), frame(2, "main", 2) }
+    end
+
+    local function extmarks()
+        return vim.api.nvim_buf_get_extmarks(0, ns, 0, -1, { details = true })
+    end
+
+    it("should read the frames GDB lists", function()
+        fake_mi.install(function(command)
+            assert.equals("-stack-list-frames", command)
+            return { class = "done", results = { stack = stack() } }
+        end)
+
+        callstack.refresh()
+
+        local frames = callstack.get_frames()
+        assert.equals(3, #frames)
+        assert.equals("run", frames[2].func)
+        assert.equals(file, frames[2].file)
+        assert.equals(5, frames[2].line)
+        assert.equals(0, callstack.get_selected().level)
+    end)
+
+    it("should highlight the current line and mark the callers", function()
+        callstack.update(stack())
+
+        local marks = extmarks()
+        assert.equals(3, #marks)
+        local by_line = {}
+        for _, mark in ipairs(marks) do
+            by_line[mark[2] + 1] = mark[4]
+        end
+        assert.equals("RustTermdebugCurrentFrame", by_line[8].line_hl_group)
+        assert.equals("◂ #1 run", by_line[5].virt_text[1][1])
+        assert.equals("◂ #2 main", by_line[2].virt_text[1][1])
+    end)
+
+    it("should move up and down the stack and follow with the cursor", function()
+        callstack.update(stack())
+
+        callstack.up()
+        assert.equals(1, callstack.get_selected().level)
+        assert.equals(5, vim.api.nvim_win_get_cursor(0)[1])
+
+        callstack.up(1)
+        assert.equals(2, callstack.get_selected().level)
+        assert.equals(2, vim.api.nvim_win_get_cursor(0)[1])
+
+        callstack.down(2)
+        assert.equals(0, callstack.get_selected().level)
+        assert.equals(8, vim.api.nvim_win_get_cursor(0)[1])
+    end)
+
+    it("should stop at the ends of the stack", function()
+        callstack.update(stack())
+
+        callstack.up(5)
+        assert.equals(2, callstack.get_selected().level)
+        assert.equals(2, vim.api.nvim_win_get_cursor(0)[1])
+
+        callstack.down(5)
+        assert.equals(0, callstack.get_selected().level)
+        assert.equals(8, vim.api.nvim_win_get_cursor(0)[1])
+    end)
+
+    it("should only say it's at the end when it already is", function()
+        local messages = {}
+        local notify = vim.notify
+        vim.notify = function(msg)
+            table.insert(messages, msg)
+        end
+        local ok, err = pcall(function()
+            callstack.update(stack())
+
+            callstack.down()
+            callstack.up(3)
+            callstack.up()
+        end)
+        vim.notify = notify
+        assert(ok, err)
+
+        assert.same({ "Already at the innermost frame", "Already at the outermost frame" }, messages)
+        assert.equals(2, callstack.get_selected().level)
+    end)
+
+    it("should clear the highlights when the program continues", function()
+        callstack.update(stack())
+
+        callstack.clear()
+
+        assert.equals(0, #extmarks())
+        assert.is_nil(callstack.get_selected())
+    end)
+
+    it("should demangle Rust paths", function()
+        assert.equals("core::panicking::panic", callstack.demangle("_ZN4core9panicking5panic17h0123456789abcdefE"))
+        assert.equals(
+            "<alloc::vec::Vec<T> as Drop>::drop",
+            callstack.demangle("_ZN49_$LT$alloc..vec..Vec$LT$T$GT$$u20$as$u20$Drop$GT$4drop17habcdef0123456789E")
+        )
+        assert.equals("app::run", callstack.demangle("app::run::h0123456789abcdef"))
+        assert.equals("main", callstack.demangle("main"))
+    end)
+
+    it("should tell library frames from workspace frames", function()
+        assert.is_false(callstack.is_library({ file = "/home/me/app/src/main.rs" }))
+        assert.is_true(callstack.is_library({ file = "/rustc/abc123/library/core/src/ops/function.rs" }))
+        assert.is_true(callstack.is_library({ file = "/home/me/.cargo/registry/src/index/tokio-1.0/src/lib.rs" }))
+        assert.is_true(callstack.is_library({ func = "clone" }))
+    end)
+
+    it("should demangle the names of frames from GDB", function()
+        local frames = stack()
+        frames[1].func = "_ZN3app4step17h0123456789abcdefE"
+        callstack.update(frames)
+
+        assert.equals("app::step", callstack.get_frames()[1].func)
+    end)
+end)
@@ -0,0 +1,84 @@
+-- Stand-in for GDB's MI channel in specs
+-- install() swaps out mi.is_active, mi.send and mi.on on the loaded mi
+-- module; restore() puts them back
+
+local fake_mi = {
+    -- Commands sent over the channel, in order
+    commands = {},
+    -- Async record handlers registered after install(), by class
+    handlers = {},
+}
+
+local mi
+local original
+
+-- Canned reply: returns the result record for a command, as an MI line or
+-- a parsed record
+local reply
+
+local function done()
+    return "1^done"
+end
+
+-- Pretend GDB is running with the MI channel open
+-- respond(command) gives the reply to each command; GDB answers ^done without it
+fake_mi.install = function(respond)
+    fake_mi.restore()
+    mi = require("mi")
+    original = { is_active = mi.is_active, send = mi.send, on = mi.on }
+    reply = respond or done
+
+    mi.is_active = function()
+        return reply ~= nil
+    end
+    mi.send = function(command, callback)
+        if not reply then
+            return false
+        end
+        table.insert(fake_mi.commands, command)
+        local record = reply(command)
+        if type(record) == "string" then
+            record = mi.parse(record)
+        end
+        if callback and record then
+            callback(record)
+        end
+        return true
+    end
+    mi.on = function(class, handler)
+        fake_mi.handlers[class] = fake_mi.handlers[class] or {}
+        table.insert(fake_mi.handlers[class], handler)
+    end
+end
+
+-- Change the canned reply of an installed fake
+fake_mi.reply = function(respond)
+    reply = respond or done
+end
+
+-- GDB has gone away: the MI channel is closed and sends fail
+fake_mi.stop = function()
+    reply = nil
+end
+
+-- Deliver an async record (eg "stopped" or "breakpoint-created") to the handlers
+fake_mi.emit = function(class, results)
+    for _, handler in ipairs(fake_mi.handlers[class] or {}) do
+        handler({ type = "notify", class = class, results = results or {} })
+    end
+end
+
+-- Put the real MI functions back
+fake_mi.restore = function()
+    if original then
+        mi.is_active = original.is_active
+        mi.send = original.send
+        mi.on = original.on
+    end
+    original = nil
+    reply = nil
+    fake_mi.commands = {}
+    fake_mi.handlers = {}
+end
+
+return fake_mi
@@ -0,0 +1,88 @@
+-- Tests for the GDB/MI output parser
+-- Run with: nvim --headless -c "PlenaryBustedDirectory tests/ {minimal_init = 'tests/minimal_init.lua'}"
+
+describe("mi module", function()
+    local mi
+
+    before_each(function()
+        package.loaded["mi"] = nil
+        mi = require("mi")
+    end)
+
+    describe("parse", function()
+        it("should parse a result record with a token", function()
+            local record = mi.parse('7^done,value="42"')
+
+            assert.equals(7, record.token)
+            assert.equals("result", record.type)
+            assert.equals("done", record.class)
+            assert.equals("42", record.results.value)
+        end)
+
+        it("should parse a result record without results", function()
+            local record = mi.parse("^done")
+
+            assert.is_nil(record.token)
+            assert.equals("done", record.class)
+            assert.same({}, record.results)
+        end)
+
+        it("should parse nested tuples and lists of results", function()
+            local record = mi.parse(
+                '3^done,BreakpointTable={nr_rows="2",body=['
+                    .. 'bkpt={number="1",fullname="/src/main.rs",line="3",times="5"},'
+                    .. 'bkpt={number="2",fullname="/src/lib.rs",line="10",times="0"}]}'
+            )
+
+            local body = record.results.BreakpointTable.body
+            assert.equals(2, #body)
+            assert.equals("1", body[1].number)
+            assert.equals("/src/main.rs", body[1].fullname)
+            assert.equals("5", body[1].times)
+            assert.equals("/src/lib.rs", body[2].fullname)
+        end)
+
+        it("should parse async records", function()
+            local record = mi.parse(
+                '*stopped,reason="breakpoint-hit",bkptno="1",frame={func="app::main",args=[],line="3"}'
+            )
+
+            assert.equals("exec", record.type)
+            assert.equals("stopped", record.class)
+            assert.equals("breakpoint-hit", record.results.reason)
+            assert.equals("app::main", record.results.frame.func)
+            assert.same({}, record.results.frame.args)
+
+            record = mi.parse('=breakpoint-deleted,id="3"')
+            assert.equals("notify", record.type)
+            assert.equals("breakpoint-deleted", record.class)
+            assert.equals("3", record.results.id)
+        end)
+
+        it("should unescape C strings", function()
+            local record = mi.parse('^error,msg="No symbol \\"x\\" in current context.\\n"')
+
+            assert.equals('No symbol "x" in current context.\n', record.results.msg)
+        end)
+
+        it("should parse stream records", function()
+            local record = mi.parse('~"Breakpoint 1 at 0x1234\\n"')
+
+            assert.equals("console", record.type)
+            assert.equals("Breakpoint 1 at 0x1234\n", record.text)
+        end)
+
+        it("should keep unnamed trailing values", function()
+            local record = mi.parse('=breakpoint-modified,bkpt={number="1"},{number="1.1",enabled="y"}')
+
+            assert.equals("1", record.results.bkpt.number)
+            assert.equals("1.1", record.results[1].number)
+        end)
+
+        it("should ignore prompts and echoed commands", function()
+            assert.is_nil(mi.parse("(gdb) "))
+            assert.is_nil(mi.parse("12-break-list"))
+            assert.is_nil(mi.parse(""))
+        end)
+    end)
+end)
@@ -36,3 +36,6 @@ vim.opt.runtimepath:prepend(plugin_dir)
 
 -- Make sure lua modules can be required from the plugin
 package.path = plugin_dir .. "/lua/?.lua;" .. plugin_dir .. "/lua/?/init.lua;" .. package.path
+
+-- Shared spec helpers, eg require("helpers.fake_mi")
+package.path = plugin_dir .. "/tests/?.lua;" .. package.path
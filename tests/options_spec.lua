@@ -37,6 +37,7 @@ describe("options module", function()
             assert.is_table(defaults.persist_breakpoints)
             assert.is_false(defaults.persist_breakpoints.enabled)
             assert.equals("exact", defaults.persist_breakpoints.line_locator)
+            assert.is_false(defaults.persist_breakpoints.per_branch)
             assert.is_false(defaults.enable_telescope)
         end)
 
@@ -89,6 +90,21 @@ describe("options module", function()
             assert.is_true(options.current.keep_cursor_in_place)
         end)
 
+        it("should normalize catch_panics boolean to table", function()
+            options.init({ catch_panics = true })
+
+            assert.is_table(options.current.catch_panics)
+            assert.is_true(options.current.catch_panics.enabled)
+            assert.same({}, options.current.catch_panics.extra_symbols)
+        end)
+
+        it("should allow extra catch_panics symbols", function()
+            options.init({ catch_panics = { extra_symbols = { "anyhow::Error::msg" } } })
+
+            assert.is_false(options.current.catch_panics.enabled)
+            assert.same({ "anyhow::Error::msg" }, options.current.catch_panics.extra_symbols)
+        end)
+
         it("should allow custom pin_suffix", function()
             options.init({ pin_suffix = " 📌" })
 
@@ -181,6 +197,21 @@ describe("options module", function()
             assert.equals("hash", options.current.persist_breakpoints.line_locator)
         end)
 
+        it("should accept a list of line locators to chain", function()
+            options.init({
+                persist_breakpoints = {
+                    enabled = true,
+                    line_locator = { "exact_if_unchanged", "hash", "jaccard" },
+                },
+            })
+
+            assert.same(
+                { "exact_if_unchanged", "hash", "jaccard" },
+                options.current.persist_breakpoints.line_locator
+            )
+            assert.equals(0.8, options.current.persist_breakpoints.min_confidence)
+        end)
+
         it("should merge partial table config with defaults", function()
             options.init({
                 persist_breakpoints = {
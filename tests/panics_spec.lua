@@ -0,0 +1,150 @@
+-- Tests for stopping on Rust panics
+-- Run with: nvim --headless -c "PlenaryBustedDirectory tests/ {minimal_init = 'tests/minimal_init.lua'}"
+
+describe("panics module", function()
+    local panics
+    local fake_mi = require("helpers.fake_mi")
+    local options
+    local test_dir
+    local gdb_commands
+    local original_send_command
+
+    local SYMBOLS = { "rust_panic", "core::panicking::panic_fmt", "std::panicking::begin_panic_handler" }
+
+    before_each(function()
+        test_dir = vim.fn.tempname()
+        vim.fn.mkdir(test_dir, "p")
+        vim.cmd("cd " .. vim.fn.fnameescape(test_dir))
+
+        package.loaded["mi"] = nil
+        package.loaded["panics"] = nil
+        options = require("options")
+        options.init({})
+        panics = require("panics")
+
+        gdb_commands = {}
+        original_send_command = vim.fn.TermDebugSendCommand
+        vim.fn.TermDebugSendCommand = function(command)
+            table.insert(gdb_commands, command)
+        end
+    end)
+
+    after_each(function()
+        fake_mi.restore()
+        vim.fn.TermDebugSendCommand = original_send_command
+        options.init({})
+        vim.cmd("cd -")
+        vim.fn.delete(test_dir, "rf")
+    end)
+
+    -- GDB has gone away: the MI channel is closed
+    local function stop_gdb()
+        fake_mi.install()
+        fake_mi.stop()
+    end
+
+    local function inserted_symbols()
+        local symbols = {}
+        for _, command in ipairs(fake_mi.commands) do
+            table.insert(symbols, command:match("^%-break%-insert %-f (.+)$"))
+        end
+        return symbols
+    end
+
+    describe("is_enabled", function()
+        it("should follow the configured default", function()
+            assert.is_false(panics.is_enabled())
+
+            options.init({ catch_panics = true })
+            assert.is_true(panics.is_enabled())
+        end)
+
+        it("should prefer the workspace setting from toggle", function()
+            options.init({ catch_panics = true })
+
+            panics.toggle()
+
+            assert.is_false(panics.is_enabled())
+            local settings = vim.json.decode(table.concat(vim.fn.readfile(".rust-termdebug.nvim/settings.json"), "\n"))
+            assert.is_false(settings.catch_panics)
+        end)
+    end)
+
+    describe("toggle", function()
+        it("should arm the panic breakpoints in a running session", function()
+            fake_mi.install()
+
+            panics.toggle()
+
+            assert.is_true(panics.is_enabled())
+            assert.same(SYMBOLS, inserted_symbols())
+        end)
+
+        it("should disarm them when toggled off", function()
+            fake_mi.install()
+            panics.toggle()
+
+            panics.toggle()
+
+            assert.is_false(panics.is_enabled())
+            local cleared = {}
+            for _, symbol in ipairs(SYMBOLS) do
+                table.insert(cleared, "clear " .. symbol)
+            end
+            assert.same(cleared, gdb_commands)
+        end)
+
+        it("should only remember the setting without GDB", function()
+            stop_gdb()
+
+            panics.toggle()
+
+            assert.is_true(panics.is_enabled())
+            assert.same({}, gdb_commands)
+        end)
+
+        it("should include extra symbols from the options", function()
+            options.init({ catch_panics = { extra_symbols = { "anyhow::Error::new" } } })
+            fake_mi.install()
+
+            panics.toggle()
+
+            assert.equals("anyhow::Error::new", inserted_symbols()[4])
+            assert.is_true(panics.is_panic_symbol("anyhow::Error::new"))
+        end)
+    end)
+
+    describe("arm", function()
+        it("should arm again after GDB restarts", function()
+            fake_mi.install()
+            panics.toggle()
+            stop_gdb()
+
+            -- A new session arms the enabled panic breakpoints on start
+            fake_mi.install()
+            panics.restore()
+
+            assert.same(SYMBOLS, inserted_symbols())
+        end)
+
+        it("should not arm on restart when disabled", function()
+            fake_mi.install()
+
+            panics.restore()
+
+            assert.same({}, fake_mi.commands)
+        end)
+
+        it("should fall back to the GDB console without the MI channel", function()
+            stop_gdb()
+
+            panics.arm()
+
+            local breaks = {}
+            for _, symbol in ipairs(SYMBOLS) do
+                table.insert(breaks, "break " .. symbol)
+            end
+            assert.same(breaks, gdb_commands)
+        end)
+    end)
+end)
@@ -2,6 +2,7 @@
 -- These tests simulate real-world scenarios like git branch switching
 
 describe("breakpoint persistence integration", function()
+    local state_file = require("state_file")
     local breakpoints
     local test_dir
     local main_rs_path
@@ -54,6 +55,24 @@ describe("breakpoint persistence integration", function()
         end
     end
 
+    -- Simulate restarting Neovim: wipe the buffer and load the breakpoints again
+    local function reload(persist_config)
+        vim.cmd("bwipeout!")
+        package.loaded["breakpoints"] = nil
+        breakpoints = require("breakpoints")
+        breakpoints.set_persistence(persist_config)
+        breakpoints.load_from_disk()
+    end
+
+    -- Set a breakpoint on a line of main.rs and save it
+    local function break_at(persist_config, line)
+        breakpoints.set_persistence(persist_config)
+        vim.cmd("edit " .. vim.fn.fnameescape(main_rs_path))
+        vim.api.nvim_win_set_cursor(0, { line, 0 })
+        breakpoints.create()
+        breakpoints.save_to_disk({ sync = true })
+    end
+
     before_each(function()
         -- Set up fresh project
         setup_cargo_project()
@@ -74,6 +93,9 @@ describe("breakpoint persistence integration", function()
         -- Close all buffers
         vim.cmd("bufdo bwipeout!")
 
+        -- Finish pending writes before the directory goes away
+        state_file.flush()
+
         -- Return to original directory
         vim.cmd("cd -")
 
@@ -580,6 +602,573 @@ describe("breakpoint persistence integration", function()
             local bps = breakpoints.get_all()
             assert.equals(0, #bps)
         end)
+
+        describe("settings", function()
+            local jaccard = require("line_locators.jaccard")
+
+            -- Save a breakpoint on `let x = 42;`, then replace the file with one
+            -- where only a far away line resembles it
+            local function restore_far_match(config)
+                breakpoints.set_persistence(config)
+                vim.cmd("edit " .. vim.fn.fnameescape(main_rs_path))
+                vim.api.nvim_win_set_cursor(0, { 3, 0 })
+                breakpoints.create()
+                breakpoints.save_to_disk({ sync = true })
+                vim.cmd("bwipeout!")
+
+                local content = { "fn main() {" }
+                for i = 1, 40 do
+                    table.insert(content, "    step_" .. i .. "();")
+                end
+                table.insert(content, "    let x = 7;")
+                table.insert(content, "}")
+                vim.fn.writefile(content, main_rs_path)
+
+                package.loaded["breakpoints"] = nil
+                breakpoints = require("breakpoints")
+                breakpoints.set_persistence(config)
+                breakpoints.load_from_disk()
+                return breakpoints.get_all()
+            end
+
+            after_each(function()
+                jaccard.configure(nil)
+            end)
+
+            it("should not match a short line to a similar one far away by default", function()
+                local bps = restore_far_match(persist_config)
+                assert.equals(0, #bps)
+            end)
+
+            it("should match far away lines by similarity alone without a distance weight", function()
+                local bps = restore_far_match({
+                    enabled = true,
+                    line_locator = "jaccard",
+                    jaccard = { distance_weight = 0 },
+                })
+                assert.equals(1, #bps)
+                assert.equals(42, bps[1].line)
+            end)
+
+            it("should honor a stricter threshold", function()
+                local bps = restore_far_match({
+                    enabled = true,
+                    line_locator = "jaccard",
+                    jaccard = { threshold = 0.75 },
+                })
+                assert.equals(0, #bps)
+            end)
+
+            it("should keep paths, lifetimes and macros whole with the rust tokenizer", function()
+                local tokens = jaccard.tokenize_rust("let s: &'a str = std::mem::take(&mut buf); println!(\"{}\", 'c');")
+
+                assert.is_true(tokens["std::mem::take"])
+                assert.is_true(tokens["'a"])
+                assert.is_true(tokens["println!"])
+                assert.is_nil(tokens["std"])
+                assert.is_nil(tokens["'c"])
+            end)
+
+            it("should tell comparisons from macros with the rust tokenizer", function()
+                local expected = { x = true, y = true, ["!="] = true }
+
+                assert.same(expected, jaccard.tokenize_rust("x != y"))
+                assert.same(expected, jaccard.tokenize_rust("x!=y"))
+                assert.same({ ["assert!"] = true, x = true, ["=="] = true, y = true }, jaccard.tokenize_rust("assert!(x == y)"))
+            end)
+
+            it("should use a custom tokenizer", function()
+                jaccard.configure({
+                    jaccard = {
+                        tokenizer = function(line)
+                            return { [vim.trim(line)] = true }
+                        end,
+                    },
+                })
+
+                assert.same({ ["let x = 42;"] = true }, jaccard.tokenize("    let x = 42;"))
+            end)
+        end)
+    end)
+
+    describe("treesitter line_locator", function()
+        local persist_config = { enabled = true, line_locator = "treesitter" }
+        local has_parser = pcall(vim.treesitter.get_string_parser, "", "rust")
+
+        local source = {
+            "struct Point {",
+            "    x: i32,",
+            "}",
+            "",
+            "impl Point {",
+            "    fn new(x: i32) -> Self {",
+            "        Point { x }",
+            "    }",
+            "",
+            "    fn shifted(&self, by: i32) -> i32 {",
+            "        let moved = self.x + by;",
+            "        println!(\"{}\", moved);",
+            "        moved",
+            "    }",
+            "}",
+            "",
+            "fn main() {",
+            "    let p = Point::new(1);",
+            "    p.shifted(2);",
+            "}",
+        }
+
+        before_each(function()
+            vim.fn.writefile(source, main_rs_path)
+        end)
+
+        it("should follow a statement when its function moves and is reformatted", function()
+            if not has_parser then
+                pending("needs the rust tree-sitter parser")
+                return
+            end
+            break_at(persist_config, 12) -- println!("{}", moved);
+
+            -- shifted moves above new, gains a comment, and its first statement is wrapped
+            vim.fn.writefile({
+                "struct Point {",
+                "    x: i32,",
+                "}",
+                "",
+                "impl Point {",
+                "    fn shifted(&self, by: i32) -> i32 {",
+                "        // move along the x axis",
+                "        let moved =",
+                "            self.x + by;",
+                "        println!(\"{}\", moved);",
+                "        moved",
+                "    }",
+                "",
+                "    fn new(x: i32) -> Self {",
+                "        Point { x }",
+                "    }",
+                "}",
+            }, main_rs_path)
+            reload(persist_config)
+
+            local bps = breakpoints.get_all()
+            assert.equals(1, #bps)
+            assert.equals(10, bps[1].line)
+        end)
+
+        it("should stay on its statement when statements are added above it", function()
+            if not has_parser then
+                pending("needs the rust tree-sitter parser")
+                return
+            end
+            break_at(persist_config, 12) -- println!("{}", moved);
+
+            local content = vim.list_extend({}, source)
+            table.insert(content, 11, "        let by = by * 2;")
+            vim.fn.writefile(content, main_rs_path)
+            reload(persist_config)
+
+            local bps = breakpoints.get_all()
+            assert.equals(1, #bps)
+            assert.equals(13, bps[1].line)
+            assert.is_nil(bps[1].unconfirmed)
+        end)
+
+        it("should mark the breakpoint unconfirmed when its statement was edited", function()
+            if not has_parser then
+                pending("needs the rust tree-sitter parser")
+                return
+            end
+            break_at(persist_config, 12) -- println!("{}", moved);
+
+            local content = vim.list_extend({}, source)
+            content[12] = '        eprintln!("moved to {}", moved);'
+            vim.fn.writefile(content, main_rs_path)
+            reload(persist_config)
+
+            local bps = breakpoints.get_all()
+            assert.equals(1, #bps)
+            assert.equals(12, bps[1].line)
+            assert.is_true(bps[1].unconfirmed)
+        end)
+
+        it("should tell functions with the same name apart by their impl", function()
+            if not has_parser then
+                pending("needs the rust tree-sitter parser")
+                return
+            end
+            vim.fn.writefile({
+                "impl A {",
+                "    fn run(&self) {",
+                "        one();",
+                "    }",
+                "}",
+                "impl B {",
+                "    fn run(&self) {",
+                "        two();",
+                "    }",
+                "}",
+            }, main_rs_path)
+            break_at(persist_config, 8) -- two();
+
+            vim.fn.writefile({
+                "impl B {",
+                "    fn run(&self) {",
+                "        two();",
+                "    }",
+                "}",
+                "impl A {",
+                "    fn run(&self) {",
+                "        one();",
+                "    }",
+                "}",
+            }, main_rs_path)
+            reload(persist_config)
+
+            local bps = breakpoints.get_all()
+            assert.equals(1, #bps)
+            assert.equals(3, bps[1].line)
+        end)
+
+        it("should skip the breakpoint when its function is gone", function()
+            if not has_parser then
+                pending("needs the rust tree-sitter parser")
+                return
+            end
+            break_at(persist_config, 11) -- let moved = self.x + by;
+
+            vim.fn.writefile({ "fn main() {", "}" }, main_rs_path)
+            reload(persist_config)
+
+            assert.equals(0, #breakpoints.get_all())
+        end)
+
+        it("should restore lines outside functions at their saved line", function()
+            break_at(persist_config, 2) -- x: i32,
+
+            reload(persist_config)
+
+            local bps = breakpoints.get_all()
+            assert.equals(1, #bps)
+            assert.equals(2, bps[1].line)
+        end)
+    end)
+
+    describe("context line_locator", function()
+        local persist_config = { enabled = true, line_locator = "context" }
+
+        it("should tell apart repeated lines by their context", function()
+            vim.fn.writefile({
+                "fn first() -> Result<()> {",
+                "    setup()?;",
+                "    Ok(())",
+                "}",
+                "fn second() -> Result<()> {",
+                "    teardown()?;",
+                "    Ok(())",
+                "}",
+            }, main_rs_path)
+            break_at(persist_config, 7) -- second's Ok(())
+
+            vim.fn.writefile({
+                "fn second() -> Result<()> {",
+                "    teardown()?;",
+                "    Ok(())",
+                "}",
+                "fn first() -> Result<()> {",
+                "    setup()?;",
+                "    Ok(())",
+                "}",
+            }, main_rs_path)
+            reload(persist_config)
+
+            local bps = breakpoints.get_all()
+            assert.equals(1, #bps)
+            assert.equals(3, bps[1].line)
+        end)
+
+        it("should find an edited line by its surroundings", function()
+            break_at(persist_config, 4) -- let y = x + 1;
+
+            vim.fn.writefile({
+                "// header",
+                "fn main() {",
+                '    println!("Line 2");',
+                "    let x = 42;",
+                "    let total = x.saturating_add(1);",
+                '    println!("y = {}", y);',
+                "    let z = y * 2;",
+                '    println!("z = {}", z);',
+                "}",
+            }, main_rs_path)
+            reload(persist_config)
+
+            local bps = breakpoints.get_all()
+            assert.equals(1, #bps)
+            assert.equals(5, bps[1].line)
+        end)
+
+        it("should skip the breakpoint when neither the line nor its context remain", function()
+            break_at(persist_config, 4)
+
+            vim.fn.writefile({ "fn main() {", "    run();", "}" }, main_rs_path)
+            reload(persist_config)
+
+            assert.equals(0, #breakpoints.get_all())
+        end)
+    end)
+
+    describe("git line_locator", function()
+        local persist_config = { enabled = true, line_locator = "git" }
+        local has_git = vim.fn.executable("git") == 1
+
+        local function git(...)
+            vim.fn.system(vim.list_extend({ "git", "-C", test_dir }, { ... }))
+        end
+
+        local function commit_all(message)
+            git("add", "-A")
+            git("-c", "user.name=test", "-c", "user.email=test@example.com", "commit", "-q", "-m", message)
+        end
+
+        it("should follow lines moved by later commits", function()
+            if not has_git then
+                pending("needs git")
+                return
+            end
+            git("init", "-q")
+            commit_all("initial")
+            break_at(persist_config, 6) -- let z = y * 2;
+
+            -- Another branch adds a duplicate of the line above it
+            vim.fn.writefile({
+                "fn main() {",
+                "    let z = y * 2;",
+                '    println!("Line 2");',
+                "    let x = 42;",
+                "    let y = x + 1;",
+                '    println!("y = {}", y);',
+                "    let z = y * 2;",
+                '    println!("z = {}", z);',
+                "}",
+            }, main_rs_path)
+            commit_all("duplicate z")
+            reload(persist_config)
+
+            local bps = breakpoints.get_all()
+            assert.equals(1, #bps)
+            assert.equals(7, bps[1].line)
+        end)
+
+        it("should map lines saved with uncommitted changes", function()
+            if not has_git then
+                pending("needs git")
+                return
+            end
+            git("init", "-q")
+            commit_all("initial")
+
+            -- Two lines added but not committed yet
+            local content = vim.list_extend({}, original_content)
+            table.insert(content, 2, "    // setup")
+            table.insert(content, 3, "    // more setup")
+            vim.fn.writefile(content, main_rs_path)
+            break_at(persist_config, 8) -- let z = y * 2;
+
+            -- The extra lines are dropped again
+            vim.fn.writefile(original_content, main_rs_path)
+            reload(persist_config)
+
+            local bps = breakpoints.get_all()
+            assert.equals(1, #bps)
+            assert.equals(6, bps[1].line)
+        end)
+
+        it("should look up the checked out commit once per save", function()
+            if not has_git then
+                pending("needs git")
+                return
+            end
+            git("init", "-q")
+            commit_all("initial")
+            breakpoints.set_persistence(persist_config)
+            vim.cmd("edit " .. vim.fn.fnameescape(main_rs_path))
+            for _, line in ipairs({ 3, 4, 6 }) do
+                vim.api.nvim_win_set_cursor(0, { line, 0 })
+                breakpoints.create()
+            end
+
+            local systemlist = vim.fn.systemlist
+            local head_lookups = 0
+            vim.fn.systemlist = function(cmd, ...)
+                if vim.deep_equal(vim.list_slice(cmd, #cmd - 1), { "rev-parse", "HEAD" }) then
+                    head_lookups = head_lookups + 1
+                end
+                return systemlist(cmd, ...)
+            end
+            local ok, err = pcall(breakpoints.save_to_disk, { sync = true })
+            vim.fn.systemlist = systemlist
+            assert(ok, err)
+
+            assert.equals(1, head_lookups)
+            local saved = vim.json.decode(state_file.read(persistence_file))
+            assert.equals(3, #saved.breakpoints)
+            assert.is_not_nil(saved.breakpoints[1].locator_data.commit)
+        end)
+
+        it("should fall back to matching context outside a git repository", function()
+            break_at(persist_config, 4) -- let y = x + 1;
+
+            local content = vim.list_extend({ "// header" }, original_content)
+            vim.fn.writefile(content, main_rs_path)
+            reload(persist_config)
+
+            local bps = breakpoints.get_all()
+            assert.equals(1, #bps)
+            assert.equals(5, bps[1].line)
+        end)
+    end)
+
+    describe("chained line_locator", function()
+        local persist_config = {
+            enabled = true,
+            line_locator = { "exact_if_unchanged", "hash", "jaccard" },
+            min_confidence = 0.8,
+        }
+
+        it("should keep an unchanged line with the first strategy", function()
+            break_at(persist_config, 3)
+            reload(persist_config)
+
+            local bps = breakpoints.get_all()
+            assert.equals(1, #bps)
+            assert.equals(3, bps[1].line)
+            assert.equals("exact_if_unchanged", bps[1].placed_by)
+            assert.equals(1, bps[1].confidence)
+            assert.is_nil(bps[1].unconfirmed)
+        end)
+
+        it("should fall through to later strategies when the line moved", function()
+            break_at(persist_config, 3) -- let x = 42;
+
+            vim.fn.writefile(vim.list_extend({ "// header" }, original_content), main_rs_path)
+            reload(persist_config)
+
+            local bps = breakpoints.get_all()
+            assert.equals(4, bps[1].line)
+            assert.equals("hash", bps[1].placed_by)
+            assert.is_nil(bps[1].unconfirmed)
+        end)
+
+        it("should mark low-confidence placements until they're confirmed", function()
+            break_at(persist_config, 3) -- let x = 42;
+
+            local modified = vim.list_extend({}, original_content)
+            modified[3] = "    let num = 42;"
+            vim.fn.writefile(modified, main_rs_path)
+            reload(persist_config)
+
+            local bps = breakpoints.get_all()
+            assert.equals(3, bps[1].line)
+            assert.equals("jaccard", bps[1].placed_by)
+            assert.is_true(bps[1].confidence < 0.8)
+            assert.is_true(bps[1].unconfirmed)
+            assert.equals("●?", breakpoints.sign_opts(bps[1]).sign_text)
+
+            vim.cmd("edit " .. vim.fn.fnameescape(main_rs_path))
+            assert.equals(1, breakpoints.confirm_all())
+            assert.is_nil(breakpoints.get_all()[1].unconfirmed)
+        end)
+    end)
+
+    describe("orphaned breakpoints", function()
+        local persist_config = { enabled = true, line_locator = "hash" }
+
+        -- Break on `let y = x + 1;`, then edit that line so the hash no longer matches
+        local function orphan_line_4()
+            break_at(persist_config, 4)
+
+            local content = vim.list_extend({ "// header" }, original_content)
+            content[5] = "    let y = x + 2;"
+            vim.fn.writefile(content, main_rs_path)
+            reload(persist_config)
+        end
+
+        it("should keep breakpoints that couldn't be restored", function()
+            orphan_line_4()
+
+            assert.equals(0, #breakpoints.get_all())
+            local orphans = breakpoints.get_orphans()
+            assert.equals(1, #orphans)
+            assert.equals("let y = x + 1;", orphans[1].text)
+            assert.equals("hashed line location failed", orphans[1].reason)
+
+            breakpoints.save_to_disk({ sync = true })
+            local saved = vim.json.decode(state_file.read(persistence_file))
+            assert.equals(1, #saved.breakpoints)
+            assert.is_true(saved.breakpoints[1].orphaned)
+        end)
+
+        it("should restore orphans once their line is back", function()
+            orphan_line_4()
+            breakpoints.save_to_disk({ sync = true })
+
+            vim.fn.writefile(original_content, main_rs_path)
+            reload(persist_config)
+
+            local bps = breakpoints.get_all()
+            assert.equals(1, #bps)
+            assert.equals(4, bps[1].line)
+            assert.equals(0, #breakpoints.get_orphans())
+        end)
+
+        it("should suggest similar lines and re-anchor on one", function()
+            orphan_line_4()
+            local orphan = breakpoints.get_orphans()[1]
+
+            local candidates = breakpoints.orphan_candidates(orphan)
+            assert.equals(5, candidates[1].line)
+            assert.equals("let y = x + 2;", candidates[1].text)
+
+            assert.is_true(breakpoints.reanchor_orphan(orphan, candidates[1].line))
+
+            local bps = breakpoints.get_all()
+            assert.equals(1, #bps)
+            assert.equals(5, bps[1].line)
+            assert.equals(0, #breakpoints.get_orphans())
+        end)
+
+        it("should not re-anchor onto a line that already has a breakpoint", function()
+            orphan_line_4()
+            local orphan = breakpoints.get_orphans()[1]
+            vim.cmd("edit " .. vim.fn.fnameescape(main_rs_path))
+            vim.api.nvim_win_set_cursor(0, { 5, 0 })
+            breakpoints.create()
+            local occupied = breakpoints.get_all()[1]
+
+            local notify = vim.notify
+            vim.notify = function() end
+            local ok, reanchored = pcall(breakpoints.reanchor_orphan, orphan, 5)
+            vim.notify = notify
+            assert(ok, reanchored)
+
+            assert.is_false(reanchored)
+            local bps = breakpoints.get_all()
+            assert.equals(1, #bps)
+            assert.equals(occupied.id, bps[1].id)
+            assert.equals(1, #breakpoints.get_orphans())
+        end)
+
+        it("should discard orphans for good", function()
+            orphan_line_4()
+
+            assert.is_true(breakpoints.discard_orphan(breakpoints.get_orphans()[1]))
+            breakpoints.save_to_disk({ sync = true })
+
+            assert.equals(0, #breakpoints.get_orphans())
+            local saved = vim.json.decode(state_file.read(persistence_file))
+            assert.equals(0, #saved.breakpoints)
+        end)
     end)
 
     describe("extmark movement persistence", function()
@@ -630,4 +1219,221 @@ describe("breakpoint persistence integration", function()
             assert.equals(5, bps_restored[1].line)
         end)
     end)
+
+    describe("workspace-relative paths", function()
+        local persist_config = { enabled = true, line_locator = "exact" }
+
+        it("should store paths relative to the workspace root", function()
+            breakpoints.set_persistence(persist_config)
+
+            vim.cmd("edit " .. vim.fn.fnameescape(main_rs_path))
+            vim.api.nvim_win_set_cursor(0, { 3, 0 })
+            breakpoints.create()
+
+            local saved = vim.json.decode(state_file.read(persistence_file))
+            assert.equals(1, #saved.breakpoints)
+            assert.equals("src/main.rs", saved.breakpoints[1].file)
+        end)
+
+        it("should load and migrate files with absolute paths", function()
+            vim.fn.writefile({ vim.json.encode({ { file = main_rs_path, line = 4 } }) }, persistence_file)
+
+            breakpoints.set_persistence(persist_config)
+            breakpoints.load_from_disk()
+
+            local bps = breakpoints.get_all()
+            assert.equals(1, #bps)
+            assert.equals(main_rs_path, bps[1].file)
+            assert.equals(4, bps[1].line)
+
+            breakpoints.save_to_disk()
+            local saved = vim.json.decode(state_file.read(persistence_file))
+            assert.equals("src/main.rs", saved.breakpoints[1].file)
+        end)
+    end)
+
+    describe("schema versions", function()
+        local function read_saved()
+            return vim.json.decode(state_file.read(persistence_file))
+        end
+
+        it("should save a versioned envelope with metadata", function()
+            breakpoints.set_persistence({ enabled = true, line_locator = "hash" })
+
+            vim.cmd("edit " .. vim.fn.fnameescape(main_rs_path))
+            vim.api.nvim_win_set_cursor(0, { 3, 0 })
+            breakpoints.create()
+
+            local saved = read_saved()
+            assert.equals(2, saved.version)
+            assert.equals("hash", saved.metadata.locator)
+            assert.is_string(saved.metadata.created_at)
+            assert.is_not_nil(saved.breakpoints[1].locator_data.line_hash)
+        end)
+
+        it("should keep created_at across saves", function()
+            breakpoints.set_persistence({ enabled = true, line_locator = "exact" })
+            vim.fn.writefile({
+                vim.json.encode({
+                    version = 2,
+                    metadata = { locator = "exact", created_at = "2024-01-01T00:00:00Z" },
+                    breakpoints = { { file = "src/main.rs", line = 3 } },
+                }),
+            }, persistence_file)
+
+            breakpoints.load_from_disk()
+            breakpoints.save_to_disk()
+
+            assert.equals("2024-01-01T00:00:00Z", read_saved().metadata.created_at)
+        end)
+
+        it("should migrate version 1 files with the old hash format", function()
+            -- Save with the hash locator to learn the hash of line 4
+            breakpoints.set_persistence({ enabled = true, line_locator = "hash" })
+            vim.cmd("edit " .. vim.fn.fnameescape(main_rs_path))
+            vim.api.nvim_win_set_cursor(0, { 4, 0 })
+            breakpoints.create()
+            local line_hash = read_saved().breakpoints[1].locator_data.line_hash
+            breakpoints.delete_all()
+            state_file.flush()
+            vim.cmd("bwipeout!")
+
+            -- Bare array with the hash outside locator_data, and a line inserted above it
+            vim.fn.writefile({ vim.json.encode({ { file = main_rs_path, line = 4, line_hash = line_hash } }) }, persistence_file)
+            local content = vim.deepcopy(original_content)
+            table.insert(content, 1, "// new first line")
+            vim.fn.writefile(content, main_rs_path)
+
+            -- Restoring with another locator still uses the hash the entry was saved with
+            package.loaded["breakpoints"] = nil
+            breakpoints = require("breakpoints")
+            breakpoints.set_persistence({ enabled = true, line_locator = "exact" })
+            breakpoints.load_from_disk()
+
+            local bps = breakpoints.get_all()
+            assert.equals(1, #bps)
+            assert.equals(5, bps[1].line)
+        end)
+
+        it("should back up corrupt files instead of overwriting them", function()
+            breakpoints.set_persistence({ enabled = true, line_locator = "exact" })
+            vim.fn.writefile({ "[{ not json" }, persistence_file)
+
+            breakpoints.load_from_disk()
+
+            local backups = vim.fn.glob(persistence_file .. ".*.bak", false, true)
+            assert.equals(1, #backups)
+            assert.same({ "[{ not json" }, vim.fn.readfile(backups[1]))
+            assert.equals(0, vim.fn.filereadable(persistence_file))
+        end)
+
+        it("should leave files from a newer version alone and not save over them", function()
+            breakpoints.set_persistence({ enabled = true, line_locator = "exact" })
+            local newer = vim.json.encode({ version = 99, breakpoints = { { file = "src/main.rs", line = 3 } } })
+            vim.fn.writefile({ newer }, persistence_file)
+
+            local warnings = {}
+            local notify = vim.notify
+            vim.notify = function(msg, level)
+                if level == vim.log.levels.WARN then
+                    table.insert(warnings, msg)
+                end
+            end
+            local ok, err = pcall(function()
+                breakpoints.load_from_disk()
+
+                vim.cmd("edit " .. vim.fn.fnameescape(main_rs_path))
+                vim.api.nvim_win_set_cursor(0, { 4, 0 })
+                breakpoints.create()
+                breakpoints.save_to_disk({ sync = true })
+                breakpoints.sync_from_disk()
+            end)
+            vim.notify = notify
+            assert(ok, err)
+
+            assert.equals(0, #vim.fn.glob(persistence_file .. ".*.bak", false, true))
+            assert.same({ newer }, vim.fn.readfile(persistence_file))
+            assert.equals(1, #warnings)
+            assert.truthy(warnings[1]:find("newer version", 1, true))
+        end)
+    end)
+
+    describe("multiple editors", function()
+        local persist_config = { enabled = true, line_locator = "exact" }
+
+        -- Save a breakpoint file the way another Neovim instance would
+        local function save_from_other_editor(lines)
+            local entries = {}
+            for _, line in ipairs(lines) do
+                table.insert(entries, { file = "src/main.rs", line = line })
+            end
+            vim.fn.writefile({
+                vim.json.encode({ version = 2, metadata = { locator = "exact" }, breakpoints = entries }),
+            }, persistence_file)
+        end
+
+        local function lines()
+            local result = {}
+            for _, bp in ipairs(breakpoints.get_all()) do
+                table.insert(result, bp.line)
+            end
+            table.sort(result)
+            return result
+        end
+
+        before_each(function()
+            break_at(persist_config, 3)
+        end)
+
+        it("should merge breakpoints another editor saved when saving", function()
+            save_from_other_editor({ 3, 5 })
+
+            vim.api.nvim_win_set_cursor(0, { 7, 0 })
+            breakpoints.create()
+            breakpoints.save_to_disk({ sync = true })
+
+            assert.same({ 3, 5, 7 }, lines())
+            local saved = vim.json.decode(state_file.read(persistence_file))
+            assert.equals(3, #saved.breakpoints)
+        end)
+
+        it("should remove breakpoints another editor deleted", function()
+            vim.api.nvim_win_set_cursor(0, { 5, 0 })
+            breakpoints.create()
+            breakpoints.save_to_disk({ sync = true })
+
+            save_from_other_editor({ 5 })
+            breakpoints.sync_from_disk()
+
+            assert.same({ 5 }, lines())
+        end)
+
+        it("should follow a breakpoint another editor moved, keeping its attributes", function()
+            breakpoints.set_group_at(vim.api.nvim_get_current_buf(), 2, "auth")
+            breakpoints.save_to_disk({ sync = true })
+
+            -- The other editor moved it down three lines
+            local saved = vim.json.decode(state_file.read(persistence_file))
+            saved.breakpoints[1].line = 6
+            vim.fn.writefile({ vim.json.encode(saved) }, persistence_file)
+            breakpoints.sync_from_disk()
+
+            local bps = breakpoints.get_all()
+            assert.equals(1, #bps)
+            assert.equals(6, bps[1].line)
+            assert.equals("auth", bps[1].group)
+            assert.equals(saved.breakpoints[1].id, bps[1].id)
+        end)
+
+        it("should show breakpoints saved by another editor as they're written", function()
+            breakpoints.watch_file()
+
+            save_from_other_editor({ 3, 6 })
+
+            assert.is_true(vim.wait(2000, function()
+                return #breakpoints.get_all() == 2
+            end))
+            assert.same({ 3, 6 }, lines())
+        end)
+    end)
 end)
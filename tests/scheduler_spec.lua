@@ -0,0 +1,98 @@
+-- Tests for scheduler-locking modes
+-- Run with: nvim --headless -c "PlenaryBustedDirectory tests/ {minimal_init = 'tests/minimal_init.lua'}"
+
+describe("scheduler module", function()
+    local scheduler
+    local fake_mi = require("helpers.fake_mi")
+    local accept
+
+    before_each(function()
+        package.loaded["mi"] = nil
+        package.loaded["scheduler"] = nil
+
+        -- Pretend GDB is running; it accepts -gdb-set while accept is set
+        accept = true
+        fake_mi.install(function()
+            if accept then
+                return "1^done"
+            end
+            return [[1^error,msg="Target 'native' cannot support this command."]]
+        end)
+
+        scheduler = require("scheduler")
+    end)
+
+    after_each(function()
+        vim.api.nvim_exec_autocmds("User", { pattern = "TermdebugStopPost" })
+        fake_mi.restore()
+    end)
+
+    it("should set each scheduler-locking mode", function()
+        for _, mode in ipairs({ "on", "step", "replay", "off" }) do
+            assert.is_true(scheduler.set_mode(mode))
+            assert.equals(mode, scheduler.get_mode())
+            assert.equals("-gdb-set scheduler-locking " .. mode, fake_mi.commands[#fake_mi.commands])
+        end
+    end)
+
+    it("should reject unknown modes", function()
+        assert.is_false(scheduler.set_mode("sometimes"))
+        assert.is_nil(scheduler.get_mode())
+        assert.equals(0, #fake_mi.commands)
+    end)
+
+    it("should pin with the last mode when none is given", function()
+        scheduler.lock()
+        assert.equals("on", scheduler.get_mode())
+
+        scheduler.lock("step")
+        scheduler.unlock()
+        assert.equals("off", scheduler.get_mode())
+
+        scheduler.lock()
+        assert.equals("step", scheduler.get_mode())
+    end)
+
+    it("should show the pinned mode in the statusline", function()
+        assert.equals("", scheduler.statusline())
+
+        scheduler.lock("step")
+        assert.equals("sched:step", scheduler.statusline())
+
+        scheduler.unlock()
+        assert.equals("", scheduler.statusline())
+    end)
+
+    it("should apply a mode GDB refused once the program stops", function()
+        accept = false
+        scheduler.lock("step")
+        assert.equals("sched:step?", scheduler.statusline())
+
+        accept = true
+        fake_mi.emit("stopped")
+
+        assert.equals("sched:step", scheduler.statusline())
+        assert.equals(2, #fake_mi.commands)
+    end)
+
+    it("should set the mode again after a reload", function()
+        scheduler.lock("step")
+        fake_mi.commands = {}
+
+        scheduler.restore()
+
+        assert.same({ "-gdb-set scheduler-locking step" }, fake_mi.commands)
+    end)
+
+    it("should forget the mode when the debug session ends", function()
+        scheduler.lock("step")
+
+        vim.api.nvim_exec_autocmds("User", { pattern = "TermdebugStopPost" })
+
+        assert.is_nil(scheduler.get_mode())
+        assert.equals("", scheduler.statusline())
+        fake_mi.commands = {}
+        scheduler.restore()
+        assert.equals(0, #fake_mi.commands)
+    end)
+end)
@@ -0,0 +1,98 @@
+-- Tests for atomic, debounced state file writes
+-- Run with: nvim --headless -c "PlenaryBustedDirectory tests/ {minimal_init = 'tests/minimal_init.lua'}"
+
+describe("state_file module", function()
+    local state_file
+    local dir
+
+    before_each(function()
+        package.loaded["state_file"] = nil
+        state_file = require("state_file")
+        dir = vim.fn.tempname()
+        vim.fn.mkdir(dir, "p")
+    end)
+
+    after_each(function()
+        state_file.flush()
+        vim.fn.delete(dir, "rf")
+    end)
+
+    local function on_disk(path)
+        if vim.fn.filereadable(path) ~= 1 then
+            return nil
+        end
+        return table.concat(vim.fn.readfile(path), "\n")
+    end
+
+    it("should write synchronously without leaving temp files", function()
+        local path = dir .. "/breakpoints.json"
+
+        state_file.write_sync(path, "[]")
+
+        assert.equals("[]", on_disk(path))
+        assert.same({ path }, vim.fn.glob(dir .. "/*", false, true))
+    end)
+
+    it("should debounce writes and keep the latest content", function()
+        local path = dir .. "/breakpoints.json"
+
+        state_file.write(path, "first")
+        state_file.write(path, "second")
+        assert.is_nil(on_disk(path))
+        assert.is_true(state_file.exists(path))
+
+        assert.is_true(vim.wait(2000, function()
+            return on_disk(path) == "second"
+        end))
+        assert.same({ path }, vim.fn.glob(dir .. "/*", false, true))
+    end)
+
+    it("should read pending content before it's written", function()
+        local path = dir .. "/breakpoints.json"
+        state_file.write_sync(path, "old")
+
+        state_file.write(path, "new")
+
+        assert.equals("new", state_file.read(path))
+        assert.equals("new", on_disk(path))
+    end)
+
+    it("should flush pending writes", function()
+        local path = dir .. "/settings.json"
+
+        state_file.write(path, "{}")
+        state_file.flush()
+
+        assert.equals("{}", on_disk(path))
+    end)
+
+    it("should build content from a function when writing", function()
+        local path = dir .. "/breakpoints.json"
+        local calls = 0
+
+        state_file.write(path, function()
+            calls = calls + 1
+            return "built"
+        end)
+        assert.equals(0, calls)
+        state_file.flush()
+
+        assert.equals(1, calls)
+        assert.equals("built", on_disk(path))
+    end)
+
+    it("should skip the write when the content function returns nil", function()
+        local path = dir .. "/breakpoints.json"
+
+        assert.is_false(state_file.write_sync(path, function()
+            return nil
+        end))
+
+        assert.is_nil(on_disk(path))
+    end)
+
+    it("should return nil for missing files", function()
+        assert.is_nil(state_file.read(dir .. "/missing.json"))
+        assert.is_false(state_file.exists(dir .. "/missing.json"))
+    end)
+end)
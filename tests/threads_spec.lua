@@ -0,0 +1,111 @@
+-- Tests for listing and switching threads
+-- Run with: nvim --headless -c "PlenaryBustedDirectory tests/ {minimal_init = 'tests/minimal_init.lua'}"
+
+describe("threads module", function()
+    local threads
+    local fake_mi = require("helpers.fake_mi")
+    local sent
+    local original_send_command
+
+    before_each(function()
+        package.loaded["mi"] = nil
+        package.loaded["callstack"] = nil
+        package.loaded["scheduler"] = nil
+        package.loaded["threads"] = nil
+        threads = require("threads")
+
+        sent = {}
+        original_send_command = vim.fn.TermDebugSendCommand
+        vim.fn.TermDebugSendCommand = function(command)
+            table.insert(sent, command)
+        end
+    end)
+
+    after_each(function()
+        fake_mi.restore()
+        vim.fn.TermDebugSendCommand = original_send_command
+    end)
+
+    -- Pretend GDB answers each MI command with the given result record
+    local function fake_gdb(responses)
+        fake_mi.install(function(command)
+            for pattern, response in pairs(responses) do
+                if command:match(pattern) then
+                    return response
+                end
+            end
+            return '1^error,msg="unexpected"'
+        end)
+    end
+
+    it("should list threads with their names", function()
+        fake_gdb({
+            ["^%-thread%-info"] = '1^done,threads=[{id="1",target-id="Thread 0x7f (LWP 10)",name="app",'
+                .. 'frame={level="0",addr="0x1",func="app::main",fullname="/src/main.rs",line="4"},state="stopped"},'
+                .. '{id="2",target-id="Thread 0x7e (LWP 11)",name="worker-0",'
+                .. 'frame={level="0",addr="0x2",func="syscall"},state="stopped"}],current-thread-id="1"',
+        })
+
+        local list
+        assert.is_true(threads.list(function(result)
+            list = result
+        end))
+
+        assert.equals(2, #list)
+        assert.equals(1, list[1].id)
+        assert.is_true(list[1].current)
+        assert.equals("app::main", list[1].func)
+        assert.equals(4, list[1].line)
+        assert.equals("worker-0", threads.label(list[2]))
+        assert.is_false(list[2].current)
+        assert.is_nil(list[2].file)
+    end)
+
+    it("should fall back to the target id for unnamed threads", function()
+        assert.equals("Thread 0x7e (LWP 11)", threads.label({ id = 2, target_id = "Thread 0x7e (LWP 11)" }))
+    end)
+
+    it("should read a thread's innermost frames without selecting it", function()
+        fake_gdb({
+            ["^%-stack%-list%-frames"] = '1^done,stack=[frame={level="0",addr="0x2",'
+                .. 'func="_ZN3app6worker17h0123456789abcdefE",fullname="/src/worker.rs",line="12"},'
+                .. 'frame={level="1",addr="0x3",func="start_thread"}]',
+        })
+
+        local frames
+        threads.frames(2, 8, function(result)
+            frames = result
+        end)
+
+        assert.same({ "-stack-list-frames --thread 2 0 7" }, fake_mi.commands)
+        assert.equals(2, #frames)
+        assert.equals("app::worker", frames[1].func)
+        assert.equals(12, frames[1].line)
+        assert.is_nil(frames[2].file)
+        assert.equals(0, #sent)
+    end)
+
+    it("should report frames of running threads as unavailable", function()
+        fake_gdb({ ["^%-stack%-list%-frames"] = '1^error,msg="Selected thread is running."' })
+
+        local frames = "unset"
+        threads.frames(3, 8, function(result)
+            frames = result
+        end)
+
+        assert.is_nil(frames)
+    end)
+
+    it("should switch threads and pin the scheduler when asked", function()
+        threads.select(2, false)
+        assert.same({ "thread 2" }, sent)
+
+        sent = {}
+        threads.select(3, true)
+        assert.same({ "thread 3", "set scheduler-locking on" }, sent)
+    end)
+
+    it("should report that no session is running", function()
+        assert.is_false(threads.list(function() end))
+    end)
+end)
@@ -0,0 +1,136 @@
+-- Tests for watchpoint tracking
+-- Run with: nvim --headless -c "PlenaryBustedDirectory tests/ {minimal_init = 'tests/minimal_init.lua'}"
+
+describe("watchpoints module", function()
+    local watchpoints
+    local mi
+    local fake_mi = require("helpers.fake_mi")
+    local bufnr
+
+    before_each(function()
+        package.loaded["mi"] = nil
+        package.loaded["watchpoints"] = nil
+        mi = require("mi")
+        watchpoints = require("watchpoints")
+
+        bufnr = vim.api.nvim_create_buf(false, true)
+        vim.api.nvim_set_current_buf(bufnr)
+        vim.api.nvim_buf_set_lines(bufnr, 0, -1, false, {
+            "fn main() {",
+            "    let total = state.count + 1;",
+            "}",
+        })
+    end)
+
+    after_each(function()
+        fake_mi.restore()
+        watchpoints.delete_all()
+        if vim.api.nvim_buf_is_valid(bufnr) then
+            vim.api.nvim_buf_delete(bufnr, { force = true })
+        end
+    end)
+
+    -- Pretend GDB is running and answers -break-watch with the given number
+    local function fake_gdb(number)
+        fake_mi.install(function(command)
+            local key = command:match("^%-break%-watch %-r") and "hw-rwpt"
+                or command:match("^%-break%-watch %-a") and "hw-awpt"
+                or "wpt"
+            return string.format('1^done,%s={number="%s",exp="x"}', key, number)
+        end)
+    end
+
+    describe("create", function()
+        it("should keep watchpoints pending without a debug session", function()
+            watchpoints.create("watch", "state.count")
+
+            local all = watchpoints.get_all()
+            assert.equals(1, #all)
+            assert.equals("state.count", all[1].expression)
+            assert.equals("watch", all[1].kind)
+            assert.is_nil(all[1].number)
+        end)
+
+        it("should watch the expression under the cursor", function()
+            vim.api.nvim_win_set_cursor(0, { 2, 24 })
+
+            watchpoints.create("rwatch")
+
+            local all = watchpoints.get_all()
+            assert.equals("state.count", all[1].expression)
+            assert.equals("rwatch", all[1].kind)
+        end)
+
+        it("should watch the visual selection", function()
+            vim.api.nvim_buf_set_mark(bufnr, "<", 2, 16, {})
+            vim.api.nvim_buf_set_mark(bufnr, ">", 2, 30, {})
+
+            watchpoints.create_from_selection("awatch")
+
+            assert.equals("state.count + 1", watchpoints.get_all()[1].expression)
+        end)
+
+        it("should record the number GDB assigns", function()
+            fake_gdb("4")
+
+            watchpoints.create("awatch", "state.count")
+
+            assert.equals("4", watchpoints.get_all()[1].number)
+        end)
+    end)
+
+    describe("delete", function()
+        it("should delete a watchpoint", function()
+            watchpoints.create("watch", "a")
+            watchpoints.create("watch", "b")
+
+            assert.is_true(watchpoints.delete(watchpoints.get_all()[1]))
+
+            local all = watchpoints.get_all()
+            assert.equals(1, #all)
+            assert.equals("b", all[1].expression)
+        end)
+
+        it("should delete all watchpoints", function()
+            watchpoints.create("watch", "a")
+            watchpoints.create("rwatch", "b")
+
+            watchpoints.delete_all()
+
+            assert.equals(0, #watchpoints.get_all())
+        end)
+    end)
+
+    describe("scope", function()
+        it("should forget watchpoints that went out of scope", function()
+            fake_gdb("7")
+            watchpoints.create("watch", "total")
+
+            assert.is_true(watchpoints.expire("7"))
+            assert.equals(0, #watchpoints.get_all())
+        end)
+
+        it("should make watchpoints pending again before re-arming", function()
+            watchpoints.create("watch", "total")
+            fake_gdb("9")
+
+            watchpoints.restore_all()
+
+            assert.equals("9", watchpoints.get_all()[1].number)
+        end)
+
+        it("should arm again when the MI channel restarted while arming", function()
+            -- GDB never answers: the channel closes with the arm in flight
+            fake_mi.install(function()
+                return nil
+            end)
+            watchpoints.create("watch", "total")
+            mi.stop()
+
+            fake_gdb("11")
+            watchpoints.restore_all()
+
+            assert.equals("11", watchpoints.get_all()[1].number)
+        end)
+    end)
+end)